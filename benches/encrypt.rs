@@ -0,0 +1,191 @@
+//! 加密热路径基准测试
+//! 
+//! 运行: `cargo bench --bench encrypt`
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use vigenere_demo::bytes::encrypt_bytes;
+use vigenere_demo::{CharElement, NonEmptySliceRef, NonEmptyVec, StringCipher, VigenereCipher};
+
+const SIZES: [(&str, usize); 2] = [("1KB", 1024), ("1MB", 1024 * 1024)];
+
+/// 统计分配次数的全局分配器，用于验证输出缓冲区只分配一次
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+    
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+    
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// 统计执行 `f` 期间发生的分配与重新分配次数
+fn count_allocations<R>(f: impl FnOnce() -> R) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    black_box(f());
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+/// 基准测试使用的字符集：(名称, 字符集字符串)
+fn charsets() -> [(&'static str, String); 2] {
+    [
+        ("uppercase_alpha", "ABCDEFGHIJKLMNOPQRSTUVWXYZ".to_string()),
+        ("printable_ascii", (32u8..=126).map(char::from).collect()),
+    ]
+}
+
+/// 生成只包含给定字符集字符的输入文本
+fn sample_text(charset: &str, len: usize) -> String {
+    charset.chars().cycle().step_by(7).take(len).collect()
+}
+
+fn bench_string_cipher(c: &mut Criterion) {
+    let mut group = c.benchmark_group("StringCipher::encrypt");
+    group.sample_size(10);
+    
+    for (name, charset) in charsets() {
+        let cipher = StringCipher::new(&charset).unwrap();
+        for (size_name, size) in SIZES {
+            let text = sample_text(&charset, size);
+            group.throughput(Throughput::Bytes(text.len() as u64));
+            group.bench_with_input(BenchmarkId::new(name, size_name), &text, |b, text| {
+                b.iter(|| cipher.encrypt(black_box(text), black_box("SECRETKEY")).unwrap())
+            });
+        }
+    }
+    
+    group.finish();
+}
+
+fn bench_generic_cipher(c: &mut Criterion) {
+    let mut group = c.benchmark_group("VigenereCipher::encrypt");
+    
+    let charset: Vec<CharElement> = "ABCDEFGHIJKLMNOPQRSTUVWXYZ"
+        .chars()
+        .enumerate()
+        .map(|(i, ch)| CharElement::new(ch, i))
+        .collect();
+    let cipher = VigenereCipher::new(NonEmptyVec::try_from_vec(charset.clone()).unwrap());
+    let key: Vec<CharElement> = [18, 4, 2, 17, 4, 19].iter().map(|&i| charset[i].clone()).collect();
+    
+    for (size_name, size) in SIZES {
+        let plaintext: Vec<CharElement> = (0..size).map(|i| charset[i * 7 % 26].clone()).collect();
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size_name), &plaintext, |b, plaintext| {
+            b.iter(|| cipher.encrypt(black_box(plaintext), NonEmptySliceRef::new(key.as_slice()).unwrap()))
+        });
+    }
+    
+    group.finish();
+}
+
+/// 长密钥 + 短消息：每次解析密钥 vs 复用预解析的 `PreparedKey`
+fn bench_prepared_key(c: &mut Criterion) {
+    let mut group = c.benchmark_group("prepared_key");
+    
+    let cipher = StringCipher::printable_ascii();
+    let long_key: String = (32u8..=126).cycle().take(64 * 1024).map(char::from).collect();
+    let prepared = cipher.prepare_key(&long_key).unwrap();
+    let message = "Short message #42";
+    
+    group.bench_function("encrypt", |b| {
+        b.iter(|| cipher.encrypt(black_box(message), black_box(&long_key)).unwrap())
+    });
+    group.bench_function("encrypt_prepared", |b| {
+        b.iter(|| cipher.encrypt_prepared(black_box(message), black_box(&prepared)).unwrap())
+    });
+    
+    group.finish();
+}
+
+/// 输出缓冲区预分配：统计 `StringCipher::encrypt` 的分配次数并测量 1MB 输入的耗时
+/// 
+/// 输出按输入长度一次性分配，1KB 与 1MB 输入的分配次数应当相同，不随输入增长
+fn bench_output_allocation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("output_allocation");
+    let cipher = StringCipher::uppercase_alpha();
+    
+    for (size_name, size) in SIZES {
+        let text = sample_text("ABCDEFGHIJKLMNOPQRSTUVWXYZ", size);
+        let allocations = count_allocations(|| cipher.encrypt(&text, "SECRETKEY").unwrap());
+        eprintln!("StringCipher::encrypt {size_name}: {allocations} 次分配");
+    }
+    
+    let text = sample_text("ABCDEFGHIJKLMNOPQRSTUVWXYZ", 1024 * 1024);
+    group.throughput(Throughput::Bytes(text.len() as u64));
+    group.bench_with_input("StringCipher::encrypt", &text, |b, text| {
+        b.iter(|| cipher.encrypt(black_box(text), black_box("SECRETKEY")).unwrap())
+    });
+    
+    group.finish();
+}
+
+/// 对比字符查找方式：当前的线性扫描 vs 基于 HashMap 的查找
+fn bench_lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("charset_lookup");
+    
+    for (name, charset) in charsets() {
+        let text: Vec<char> = sample_text(&charset, 1024).chars().collect();
+        let charset: Vec<char> = charset.chars().collect();
+        let map: HashMap<char, usize> = charset.iter().enumerate().map(|(i, &ch)| (ch, i)).collect();
+        group.throughput(Throughput::Elements(text.len() as u64));
+        
+        group.bench_with_input(BenchmarkId::new("linear_scan", name), &text, |b, text| {
+            b.iter(|| {
+                text.iter()
+                    .map(|ch| charset.iter().position(|x| x == ch).unwrap())
+                    .sum::<usize>()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("hash_map", name), &text, |b, text| {
+            b.iter(|| text.iter().map(|ch| map[ch]).sum::<usize>())
+        });
+    }
+    
+    group.finish();
+}
+
+/// 字节密码：使用 `--features simd` 运行可与标量实现对比
+fn bench_bytes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bytes::encrypt_bytes");
+    
+    let data: Vec<u8> = (0..1024 * 1024).map(|i| (i * 37 + 11) as u8).collect();
+    for key in [b"KEY".as_slice(), b"0123456789abcdef0123456789abcdef".as_slice()] {
+        let ne_key = NonEmptySliceRef::new(key).unwrap();
+        group.throughput(Throughput::Bytes(data.len() as u64));
+        group.bench_with_input(BenchmarkId::new("1MB", format!("key_len={}", key.len())), &data, |b, data| {
+            b.iter(|| encrypt_bytes(black_box(data), ne_key))
+        });
+    }
+    
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_string_cipher,
+    bench_generic_cipher,
+    bench_output_allocation,
+    bench_prepared_key,
+    bench_lookup,
+    bench_bytes
+);
+criterion_main!(benches);
@@ -0,0 +1,36 @@
+//! 密钥缓存基准测试
+//! 
+//! 运行: `cargo bench --bench key_cache --features cache`
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use vigenere_demo::StringCipher;
+
+/// 短消息 + 轮换的长密钥：解析密钥是主要开销
+fn bench_key_cache(c: &mut Criterion) {
+    let mut group = c.benchmark_group("key_cache");
+    
+    let keys: Vec<String> = (0..8)
+        .map(|i| (32u8..=126).cycle().skip(i * 11).take(256).map(char::from).collect())
+        .collect();
+    let message = "Short message #42";
+    
+    for (name, cipher) in [
+        ("uncached", StringCipher::printable_ascii()),
+        ("cached", StringCipher::printable_ascii().with_key_cache(16)),
+    ] {
+        group.bench_function(BenchmarkId::from_parameter(name), |b| {
+            b.iter(|| {
+                for key in &keys {
+                    black_box(cipher.encrypt(black_box(message), key).unwrap());
+                }
+            })
+        });
+    }
+    
+    group.finish();
+}
+
+criterion_group!(benches, bench_key_cache);
+criterion_main!(benches);
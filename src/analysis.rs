@@ -0,0 +1,662 @@
+//! 密码分析模块
+//! 
+//! 提供针对维吉尼亚密码的经典分析工具，输入为字符集元素序列
+//! （可通过 [`StringCipher::to_elements`] 从字符串获得）
+
+use crate::{normalize, CharElement, CipherElement, CipherError, StringCipher};
+
+/// 由已知明文/密文对恢复密钥
+/// 
+/// 逐位置计算 `(c - p) mod n` 得到位移，并假设密钥周期为 `key_len`，
+/// 检查同一密钥位置上的位移是否一致
+/// 
+/// # 返回
+/// 一致时返回长度为 `key_len` 的密钥元素；长度不匹配、`key_len` 为 0、
+/// 明文不足以覆盖整个周期或位移不一致时返回 `None`
+/// 
+/// # 示例
+/// ```
+/// use vigenere_demo::{CipherElement, StringCipher};
+/// use vigenere_demo::analysis::recover_key_from_pair;
+/// 
+/// let cipher = StringCipher::uppercase_alpha();
+/// let plaintext = cipher.to_elements("HELLOWORLD");
+/// let ciphertext = cipher.to_elements(&cipher.encrypt("HELLOWORLD", "KEY").unwrap());
+/// 
+/// let key = recover_key_from_pair(&plaintext, &ciphertext, &cipher, 3).unwrap();
+/// let key: String = key.iter().map(|e| e.value()).collect();
+/// assert_eq!(key, "KEY");
+/// ```
+pub fn recover_key_from_pair(
+    plaintext: &[CharElement],
+    ciphertext: &[CharElement],
+    cipher: &StringCipher,
+    key_len: usize,
+) -> Option<Vec<CharElement>> {
+    if key_len == 0 || plaintext.len() != ciphertext.len() || plaintext.len() < key_len {
+        return None;
+    }
+    
+    let n = cipher.modulus;
+    let mut shifts: Vec<Option<usize>> = vec![None; key_len];
+    
+    for (i, (p, c)) in plaintext.iter().zip(ciphertext).enumerate() {
+        let shift = (c.index() + n - p.index()) % n;
+        match shifts[i % key_len] {
+            Some(existing) if existing != shift => return None,
+            _ => shifts[i % key_len] = Some(shift),
+        }
+    }
+    
+    shifts
+        .into_iter()
+        .map(|shift| shift.map(|s| cipher.charset[s].clone()))
+        .collect()
+}
+
+/// 拖动已知明文片段（crib），在每个偏移处求出对应的候选密钥片段
+/// 
+/// 对每个偏移 `i`，计算 `(ciphertext[i + j] - crib[j]) mod n` 得到候选密钥片段。
+/// 片段中出现可读的单词或重复模式时，说明 crib 很可能出现在该位置。
+/// `crib` 为空或比密文长时返回空序列
+/// 
+/// # 示例
+/// ```
+/// use vigenere_demo::{map_values, StringCipher};
+/// use vigenere_demo::analysis::crib_drag;
+/// 
+/// let cipher = StringCipher::uppercase_alpha();
+/// let ciphertext = cipher.to_elements(&cipher.encrypt("ATTACKATDAWN", "LEMON").unwrap());
+/// let candidates = crib_drag(&ciphertext, &cipher.to_elements("ATTACK"), &cipher);
+/// assert_eq!(candidates.len(), 7);
+/// assert_eq!(candidates[0].0, 0);
+/// assert_eq!(map_values(&candidates[0].1), ['L', 'E', 'M', 'O', 'N', 'L']);
+/// ```
+pub fn crib_drag(
+    ciphertext: &[CharElement],
+    crib: &[CharElement],
+    cipher: &StringCipher,
+) -> Vec<(usize, Vec<CharElement>)> {
+    if crib.is_empty() || crib.len() > ciphertext.len() {
+        return Vec::new();
+    }
+    
+    let n = cipher.modulus;
+    ciphertext
+        .windows(crib.len())
+        .enumerate()
+        .map(|(offset, window)| {
+            let fragment = window
+                .iter()
+                .zip(crib)
+                .map(|(c, p)| cipher.charset[(c.index() + n - p.index()) % n].clone())
+                .collect();
+            (offset, fragment)
+        })
+        .collect()
+}
+
+/// 计算两个等长序列在字符集上的汉明距离（索引不同的位置数）
+/// 
+/// 长度不同时返回 `None`。用同一密钥加密的两条消息，其密文的汉明距离
+/// 等于明文的汉明距离，这是演示密钥复用风险的基础
+/// 
+/// # 示例
+/// ```
+/// use vigenere_demo::StringCipher;
+/// use vigenere_demo::analysis::charset_hamming;
+/// 
+/// let cipher = StringCipher::uppercase_alpha();
+/// let a = cipher.to_elements("KAROLIN");
+/// let b = cipher.to_elements("KATHRIN");
+/// assert_eq!(charset_hamming(&a, &b), Some(3));
+/// ```
+pub fn charset_hamming(a: &[CharElement], b: &[CharElement]) -> Option<usize> {
+    if a.len() != b.len() {
+        return None;
+    }
+    
+    Some(a.iter().zip(b).filter(|(x, y)| x.index() != y.index()).count())
+}
+
+/// 逐位置相减两条密文：`(a[i] - b[i]) mod n`
+/// 
+/// 两条密文使用同一密钥且对齐时，密钥在相减中抵消，结果等于两条明文之差
+/// `(p1[i] - p2[i]) mod n`。已知其中一条明文即可还原另一条，这正是密钥复用
+/// （“两次一密”）的致命之处。长度不同时返回 `None`
+/// 
+/// # 示例
+/// ```
+/// use vigenere_demo::StringCipher;
+/// use vigenere_demo::analysis::subtract_ciphertexts;
+/// 
+/// let cipher = StringCipher::uppercase_alpha();
+/// let c1 = cipher.to_elements(&cipher.encrypt("HELLO", "KEY").unwrap());
+/// let c2 = cipher.to_elements(&cipher.encrypt("HELLO", "KEY").unwrap());
+/// // 明文相同，差全为 0
+/// let diff = subtract_ciphertexts(&c1, &c2, &cipher).unwrap();
+/// assert_eq!(diff, cipher.to_elements("AAAAA"));
+/// ```
+pub fn subtract_ciphertexts(
+    a: &[CharElement],
+    b: &[CharElement],
+    cipher: &StringCipher,
+) -> Option<Vec<CharElement>> {
+    if a.len() != b.len() {
+        return None;
+    }
+    
+    let n = cipher.modulus;
+    Some(
+        a.iter()
+            .zip(b)
+            .map(|(x, y)| cipher.charset[(x.index() + n - y.index()) % n].clone())
+            .collect(),
+    )
+}
+
+/// 从一组明文/密文对推测最小字符集
+/// 
+/// 逐位置比较两个字符串（规范化后），收集发生变化的位置上出现的所有明文和密文字符，
+/// 按码位排序后作为候选字符集返回。两侧字符相同的位置视为原样保留的字符而忽略
+/// （位移恰好为 0 的字符集字符也会因此被忽略）；长度不同时只比较公共前缀。
+/// 没有任何发生变化的位置时返回 [`CipherError::EmptyCharset`]
+/// 
+/// 推测结果只包含样本中出现过的字符，是原字符集的子集，适合在原始配置丢失时作为起点
+/// 
+/// # 示例
+/// ```
+/// use vigenere_demo::analysis::infer_charset;
+/// 
+/// let charset = infer_charset("ABC ABC", "BCA BCA").unwrap();
+/// assert_eq!(charset, "ABC");
+/// ```
+pub fn infer_charset(plaintext: &str, ciphertext: &str) -> Result<String, CipherError> {
+    let plaintext = normalize(plaintext);
+    let ciphertext = normalize(ciphertext);
+    
+    let mut chars: Vec<char> = plaintext
+        .chars()
+        .zip(ciphertext.chars())
+        .filter(|(p, c)| p != c)
+        .flat_map(|(p, c)| [p, c])
+        .collect();
+    chars.sort_unstable();
+    chars.dedup();
+    
+    if chars.is_empty() {
+        return Err(CipherError::EmptyCharset);
+    }
+    
+    Ok(chars.into_iter().collect())
+}
+
+/// 计算重合指数（Index of Coincidence）
+/// 
+/// ```text
+/// IC = Σ fᵢ(fᵢ - 1) / (N(N - 1))
+/// ```
+/// 
+/// 其中 `fᵢ` 为索引 `i` 出现的次数，`N` 为序列长度。均匀分布的 IC 约为 `1 / modulus`，
+/// 英文约为 0.066。索引不小于 `modulus` 的元素不计入统计，
+/// 参与统计的元素少于 2 个时返回 0.0
+/// 
+/// # 参数
+/// - `text`: 元素序列
+/// - `modulus`: 字符集大小
+pub fn index_of_coincidence(text: &[CharElement], modulus: usize) -> f64 {
+    let mut counts = vec![0usize; modulus];
+    let mut n = 0;
+    for elem in text {
+        if let Some(count) = counts.get_mut(elem.index()) {
+            *count += 1;
+            n += 1;
+        }
+    }
+    
+    if n < 2 {
+        return 0.0;
+    }
+    
+    let coincidences: usize = counts.iter().map(|&f| f * f.saturating_sub(1)).sum();
+    coincidences as f64 / (n * (n - 1)) as f64
+}
+
+/// 归一化 IC 低于此值视为接近均匀分布（疑似已加密），见 [`StringCipher::looks_random`]
+/// 
+/// 与 [`MONOALPHABETIC_THRESHOLD`] 之间的区间有意留空：归一化 IC 落在 1.3 ~ 1.5 之间的文本
+/// 既不够均匀，也不够接近自然语言（例如短密钥的多表替换或样本过少的明文），两个判断都返回否定结果
+pub(crate) const UNIFORM_THRESHOLD: f64 = 1.3;
+
+/// 单表替换与多表替换之间的归一化 IC 分界，见 [`is_likely_monoalphabetic`]
+pub(crate) const MONOALPHABETIC_THRESHOLD: f64 = 1.5;
+
+/// 判断密文是否可能是单表替换（如凯撒密码）
+/// 
+/// 比较归一化重合指数 `IC × modulus`：均匀分布约为 1.0，英文约为 1.73。
+/// 单表替换不改变频率分布，IC 保持自然语言水平；多表替换会把分布拉平。
+/// 归一化 IC 不低于 1.5 时返回 `true`，此时通常无需再做 Kasiski 检验。
+/// 序列长度小于 2 时返回 `false`
+/// 
+/// # 参数
+/// - `text`: 密文元素序列
+/// - `modulus`: 字符集大小
+pub fn is_likely_monoalphabetic(text: &[CharElement], modulus: usize) -> bool {
+    index_of_coincidence(text, modulus) * modulus as f64 >= MONOALPHABETIC_THRESHOLD
+}
+
+/// 综合 Kasiski 检验与重合指数，给出最可能的密钥长度
+/// 
+/// - 重合指数：对每个候选长度 `L`，把密文按位置模 `L` 拆成 `L` 组，计算各组 IC 的平均值。
+///   长度正确时每组都是单表替换，IC 接近自然语言水平
+/// - Kasiski：统计重复三元组之间的距离能被 `L` 整除的次数
+/// 
+/// 只考虑平均 IC 不低于最高值 90% 的候选长度，再按两项归一化得分之和排序。
+/// 真实长度的倍数 IC 同样很高，但 Kasiski 票数不会更多，因此更短的真实长度胜出
+/// 
+/// # 参数
+/// - `ciphertext`: 密文元素序列
+/// - `max_len`: 最大候选长度（小于 1 时按 1 处理）
+/// 
+/// # 返回
+/// 最可能的密钥长度，密文过短无法判断时返回 1
+pub fn best_key_length(ciphertext: &[CharElement], max_len: usize) -> usize {
+    let max_len = max_len.max(1).min(ciphertext.len() / 2).max(1);
+    let modulus = ciphertext.iter().map(|e| e.index() + 1).max().unwrap_or(1);
+    
+    let ioc: Vec<f64> = (1..=max_len)
+        .map(|len| average_coset_ioc(ciphertext, len, modulus))
+        .collect();
+    let votes = kasiski_votes(ciphertext, max_len);
+    
+    let max_ioc = ioc.iter().cloned().fold(0.0, f64::max);
+    let max_votes = votes.iter().cloned().max().unwrap_or(0);
+    if max_ioc == 0.0 {
+        return 1;
+    }
+    
+    // 只在 IC 接近最高值的长度中比较，Kasiski 票数作为加分，得分相同时取较短者
+    let mut best = (1, f64::MIN);
+    for i in 0..max_len {
+        if ioc[i] < 0.9 * max_ioc {
+            continue;
+        }
+        let kasiski = if max_votes == 0 { 0.0 } else { votes[i] as f64 / max_votes as f64 };
+        let score = ioc[i] / max_ioc + kasiski;
+        if score > best.1 {
+            best = (i + 1, score);
+        }
+    }
+    
+    best.0
+}
+
+/// 计算按密钥位置拆分后各组的香农熵（单位：比特）
+/// 
+/// 第 `j` 项为位置模 `key_len` 等于 `j` 的元素组成的组的熵，空组的熵为 0.0。
+/// 密钥长度正确时每组都是平移后的自然语言分布，熵明显低于均匀分布的 `log2(modulus)`；
+/// 长度错误时各组混合了多个平移，熵接近均匀分布。`key_len` 为 0 时返回空序列
+/// 
+/// # 示例
+/// ```
+/// use vigenere_demo::StringCipher;
+/// use vigenere_demo::analysis::coset_entropies;
+/// 
+/// let cipher = StringCipher::uppercase_alpha();
+/// let entropies = coset_entropies(&cipher.to_elements("AABB"), 2);
+/// assert_eq!(entropies, [1.0, 1.0]);
+/// ```
+pub fn coset_entropies(ciphertext: &[CharElement], key_len: usize) -> Vec<f64> {
+    use std::collections::HashMap;
+    
+    (0..key_len)
+        .map(|offset| {
+            let mut counts: HashMap<usize, usize> = HashMap::new();
+            for elem in ciphertext.iter().skip(offset).step_by(key_len) {
+                *counts.entry(elem.index()).or_insert(0) += 1;
+            }
+            
+            let total: usize = counts.values().sum();
+            counts
+                .values()
+                .map(|&count| {
+                    let p = count as f64 / total as f64;
+                    -p * p.log2()
+                })
+                .sum()
+        })
+        .collect()
+}
+
+/// 卡方拟合优度：比较观测到的索引频率与给定的期望分布
+/// 
+/// ```text
+/// χ² = Σ (Oᵢ - Eᵢ)² / Eᵢ
+/// ```
+/// 
+/// 其中 `Oᵢ` 为索引 `i` 出现的次数，`Eᵢ = expected_freqs[i] / Σ expected_freqs × N`。
+/// 频率表无需归一化，期望频率为 0 的索引不参与计算。分布完全一致或观测序列为空时为 0，越大差异越明显，
+/// 可用于恢复密钥和比较候选解密结果
+/// 
+/// # 参数
+/// - `observed`: 观测到的元素序列
+/// - `expected_freqs`: 期望频率表，第 `i` 项对应字符集第 `i` 个字符
+/// - `modulus`: 字符集大小
+/// 
+/// 元素本身不携带字符集大小，因此需要显式传入 `modulus`，以便校验频率表长度和元素索引；
+/// 也正因为存在这两种无效输入，返回 `Result` 而不是直接返回 `f64`
+/// 
+/// # 错误
+/// - 频率表长度与 `modulus` 不一致时返回 [`CipherError::FrequencyTableMismatch`]
+/// - 元素索引不小于 `modulus` 时返回 [`CipherError::CharNotInCharset`]
+/// 
+/// # 示例
+/// ```
+/// use vigenere_demo::StringCipher;
+/// use vigenere_demo::analysis::chi_squared;
+/// 
+/// let cipher = StringCipher::new("AB").unwrap();
+/// assert_eq!(chi_squared(&cipher.to_elements("AB"), &[0.5, 0.5], 2).unwrap(), 0.0);
+/// assert_eq!(chi_squared(&cipher.to_elements("AA"), &[0.5, 0.5], 2).unwrap(), 2.0);
+/// ```
+pub fn chi_squared(observed: &[CharElement], expected_freqs: &[f64], modulus: usize) -> Result<f64, CipherError> {
+    if expected_freqs.len() != modulus {
+        return Err(CipherError::FrequencyTableMismatch { modulus, table_len: expected_freqs.len() });
+    }
+    if let Some(elem) = observed.iter().find(|elem| elem.index() >= modulus) {
+        return Err(CipherError::CharNotInCharset(elem.value()));
+    }
+    
+    let counts = index_counts(observed, modulus);
+    Ok(chi_squared_statistic(|i| counts[i], observed.len(), expected_freqs))
+}
+
+/// 找出使单表密文最接近给定语言频率的凯撒位移（卡方统计量最小）
+/// 
+/// `freqs[i]` 为字符集第 `i` 个字符在明文中的相对频率，无需归一化
+pub(crate) fn best_caesar_shift(coset: &[CharElement], freqs: &[f64]) -> usize {
+    let modulus = freqs.len();
+    let counts = index_counts(coset, modulus);
+    let chi_squared = |shift: usize| chi_squared_statistic(|p| counts[(p + shift) % modulus], coset.len(), freqs);
+    
+    (0..modulus)
+        .min_by(|&a, &b| chi_squared(a).total_cmp(&chi_squared(b)))
+        .unwrap_or(0)
+}
+
+/// 统计各索引出现的次数
+fn index_counts(text: &[CharElement], modulus: usize) -> Vec<usize> {
+    let mut counts = vec![0usize; modulus];
+    for elem in text {
+        counts[elem.index()] += 1;
+    }
+    counts
+}
+
+/// 卡方统计量：`observed(i)` 为索引 `i` 的观测次数，`total` 为观测总数
+fn chi_squared_statistic(observed: impl Fn(usize) -> usize, total: usize, freqs: &[f64]) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    
+    let freq_sum: f64 = freqs.iter().sum();
+    (0..freqs.len())
+        .filter(|&i| freqs[i] > 0.0)
+        .map(|i| {
+            let expected = freqs[i] / freq_sum * total as f64;
+            (observed(i) as f64 - expected).powi(2) / expected
+        })
+        .sum()
+}
+
+/// 将序列按位置模 `key_len` 拆分后各组重合指数的平均值
+fn average_coset_ioc(text: &[CharElement], key_len: usize, modulus: usize) -> f64 {
+    let total: f64 = (0..key_len)
+        .map(|offset| {
+            let coset: Vec<CharElement> = text.iter().skip(offset).step_by(key_len).cloned().collect();
+            index_of_coincidence(&coset, modulus)
+        })
+        .sum();
+    total / key_len as f64
+}
+
+/// Kasiski 检验：`votes[L - 1]` 为重复三元组间距能被 `L` 整除的次数（`L = 1` 不计票）
+fn kasiski_votes(text: &[CharElement], max_len: usize) -> Vec<usize> {
+    use std::collections::HashMap;
+    
+    let mut last_seen: HashMap<[usize; 3], usize> = HashMap::new();
+    let mut votes = vec![0; max_len];
+    
+    for (pos, window) in text.windows(3).enumerate() {
+        let trigram = [window[0].index(), window[1].index(), window[2].index()];
+        if let Some(prev) = last_seen.insert(trigram, pos) {
+            let distance = pos - prev;
+            for len in 2..=max_len {
+                if distance % len == 0 {
+                    votes[len - 1] += 1;
+                }
+            }
+        }
+    }
+    
+    votes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试用英文样本（《双城记》开篇）
+    const ENGLISH: &str = "IT WAS THE BEST OF TIMES, IT WAS THE WORST OF TIMES, IT WAS THE AGE OF WISDOM, \
+        IT WAS THE AGE OF FOOLISHNESS, IT WAS THE EPOCH OF BELIEF, IT WAS THE EPOCH OF INCREDULITY, \
+        IT WAS THE SEASON OF LIGHT, IT WAS THE SEASON OF DARKNESS, IT WAS THE SPRING OF HOPE, \
+        IT WAS THE WINTER OF DESPAIR, WE HAD EVERYTHING BEFORE US, WE HAD NOTHING BEFORE US, \
+        WE WERE ALL GOING DIRECT TO HEAVEN, WE WERE ALL GOING DIRECT THE OTHER WAY. IN SHORT, \
+        THE PERIOD WAS SO FAR LIKE THE PRESENT PERIOD, THAT SOME OF ITS NOISIEST AUTHORITIES \
+        INSISTED ON ITS BEING RECEIVED, FOR GOOD OR FOR EVIL, IN THE SUPERLATIVE DEGREE OF \
+        COMPARISON ONLY. THERE WERE A KING WITH A LARGE JAW AND A QUEEN WITH A PLAIN FACE, ON THE \
+        THRONE OF ENGLAND; THERE WERE A KING WITH A LARGE JAW AND A QUEEN WITH A FAIR FACE, ON THE \
+        THRONE OF FRANCE. IN BOTH COUNTRIES IT WAS CLEARER THAN CRYSTAL TO THE LORDS OF THE STATE \
+        PRESERVES OF LOAVES AND FISHES, THAT THINGS IN GENERAL WERE SETTLED FOR EVER.";
+
+    fn values(elements: &[CharElement]) -> String {
+        elements.iter().map(|e| e.value()).collect()
+    }
+
+    #[test]
+    fn test_index_of_coincidence() {
+        let cipher = StringCipher::uppercase_alpha();
+        assert_eq!(index_of_coincidence(&cipher.to_elements("AAAA"), 26), 1.0);
+        assert_eq!(index_of_coincidence(&cipher.to_elements("ABCD"), 26), 0.0);
+        assert_eq!(index_of_coincidence(&cipher.to_elements("A"), 26), 0.0);
+        assert!((index_of_coincidence(&cipher.to_elements("AABB"), 26) - 1.0 / 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_best_key_length() {
+        let cipher = StringCipher::uppercase_alpha();
+        for key in ["CRYPTIC", "LEMON", "SECRETKEY"] {
+            let ciphertext = cipher.to_elements(&cipher.encrypt(ENGLISH, key).unwrap());
+            assert_eq!(best_key_length(&ciphertext, 20), key.len(), "key = {key}");
+        }
+        
+        assert_eq!(best_key_length(&[], 20), 1);
+        assert_eq!(best_key_length(&cipher.to_elements("ABC"), 0), 1);
+    }
+
+    #[test]
+    fn test_coset_entropies() {
+        let cipher = StringCipher::uppercase_alpha();
+        let ciphertext = cipher.to_elements(&cipher.encrypt(ENGLISH, "LEMON").unwrap());
+        let mean = |v: Vec<f64>| v.iter().sum::<f64>() / v.len() as f64;
+        
+        let correct = coset_entropies(&ciphertext, 5);
+        assert_eq!(correct.len(), 5);
+        assert!(correct.iter().all(|&h| h > 0.0 && h < 26f64.log2()));
+        assert!(mean(correct) + 0.2 < mean(coset_entropies(&ciphertext, 4)));
+        assert!(mean(coset_entropies(&ciphertext, 5)) + 0.2 < mean(coset_entropies(&ciphertext, 7)));
+        
+        assert!(coset_entropies(&ciphertext, 0).is_empty());
+        assert_eq!(coset_entropies(&[], 2), [0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_crib_drag() {
+        let cipher = StringCipher::uppercase_alpha();
+        let ciphertext = cipher.to_elements(&cipher.encrypt("ATTACK AT DAWN", "LEMON").unwrap());
+        let crib = cipher.to_elements("DAWN");
+        
+        let candidates = crib_drag(&ciphertext, &crib, &cipher);
+        assert_eq!(candidates.len(), ciphertext.len() - crib.len() + 1);
+        // "DAWN" 位于第 8 位，对应密钥位置 3, 4, 0, 1
+        let (offset, fragment) = &candidates[8];
+        assert_eq!(*offset, 8);
+        assert_eq!(values(fragment), "ONLE");
+        
+        assert!(crib_drag(&ciphertext, &[], &cipher).is_empty());
+        assert!(crib_drag(&crib, &ciphertext, &cipher).is_empty());
+    }
+
+    #[test]
+    fn test_charset_hamming() {
+        let cipher = StringCipher::uppercase_alpha();
+        let a = cipher.to_elements("ATTACKATDAWN");
+        let b = cipher.to_elements("ATTACKATDUSK");
+        assert_eq!(charset_hamming(&a, &b), Some(3));
+        assert_eq!(charset_hamming(&a, &a), Some(0));
+        assert_eq!(charset_hamming(&a, &b[..5]), None);
+        assert_eq!(charset_hamming(&[], &[]), Some(0));
+        
+        // 同一密钥下密文的汉明距离等于明文的汉明距离
+        let ea = cipher.to_elements(&cipher.encrypt("ATTACKATDAWN", "LEMON").unwrap());
+        let eb = cipher.to_elements(&cipher.encrypt("ATTACKATDUSK", "LEMON").unwrap());
+        assert_eq!(charset_hamming(&ea, &eb), Some(3));
+    }
+
+    #[test]
+    fn test_subtract_ciphertexts_key_cancels() {
+        let cipher = StringCipher::uppercase_alpha();
+        let (p1, p2) = ("ATTACKATDAWN", "RETREATATSIX");
+        let plain_diff = subtract_ciphertexts(&cipher.to_elements(p1), &cipher.to_elements(p2), &cipher).unwrap();
+        
+        for key in ["LEMON", "K", "ANOTHERLONGERKEY"] {
+            let c1 = cipher.to_elements(&cipher.encrypt(p1, key).unwrap());
+            let c2 = cipher.to_elements(&cipher.encrypt(p2, key).unwrap());
+            assert_eq!(subtract_ciphertexts(&c1, &c2, &cipher).unwrap(), plain_diff, "key = {key}");
+        }
+        
+        // 已知一条明文即可还原另一条
+        let c1 = cipher.to_elements(&cipher.encrypt(p1, "LEMON").unwrap());
+        let c2 = cipher.to_elements(&cipher.encrypt(p2, "LEMON").unwrap());
+        let diff = subtract_ciphertexts(&c1, &c2, &cipher).unwrap();
+        let recovered = subtract_ciphertexts(&cipher.to_elements(p1), &diff, &cipher).unwrap();
+        assert_eq!(values(&recovered), p2);
+        
+        assert!(subtract_ciphertexts(&c1, &c2[1..], &cipher).is_none());
+    }
+
+    #[test]
+    fn test_infer_charset() {
+        let cipher = StringCipher::new("DEHLORW").unwrap();
+        let plaintext = "HELLO, WORLD!";
+        let ciphertext = cipher.encrypt(plaintext, "LO").unwrap();
+        
+        let inferred = infer_charset(plaintext, &ciphertext).unwrap();
+        assert_eq!(inferred, "DEHLORW");
+        
+        // 推测出的字符集可以直接用于重建密码器
+        let rebuilt = StringCipher::new(&inferred).unwrap();
+        assert_eq!(rebuilt.decrypt(&ciphertext, "LO").unwrap(), plaintext);
+        
+        assert_eq!(infer_charset("SAME", "SAME").unwrap_err(), CipherError::EmptyCharset);
+    }
+
+    #[test]
+    fn test_is_likely_monoalphabetic() {
+        let cipher = StringCipher::uppercase_alpha();
+        let elements = |key: &str| cipher.to_elements(&cipher.encrypt(ENGLISH, key).unwrap());
+        
+        assert!(is_likely_monoalphabetic(&cipher.to_elements(ENGLISH), 26));
+        for key in ["D", "XXX"] {
+            assert!(is_likely_monoalphabetic(&elements(key), 26), "key = {key}");
+        }
+        for key in ["LEMON", "CRYPTIC", "SECRETKEY"] {
+            assert!(!is_likely_monoalphabetic(&elements(key), 26), "key = {key}");
+        }
+        
+        assert!(!is_likely_monoalphabetic(&[], 26));
+    }
+
+    #[test]
+    fn test_chi_squared() {
+        let cipher = StringCipher::uppercase_alpha();
+        
+        // 观测分布与期望分布一致时接近 0
+        let text = cipher.to_elements(ENGLISH);
+        let mut freqs = vec![0.0; 26];
+        for elem in &text {
+            freqs[elem.index()] += 1.0;
+        }
+        assert!(chi_squared(&text, &freqs, 26).unwrap() < 1e-9);
+        
+        // 与均匀分布或加密后的文本相比差异明显
+        let uniform = vec![1.0; 26];
+        assert!(chi_squared(&text, &uniform, 26).unwrap() > 100.0);
+        let encrypted = cipher.to_elements(&cipher.encrypt(ENGLISH, "LEMON").unwrap());
+        assert!(chi_squared(&encrypted, &freqs, 26).unwrap() > 100.0);
+        
+        assert_eq!(chi_squared(&[], &uniform, 26).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_chi_squared_table_mismatch() {
+        let cipher = StringCipher::uppercase_alpha();
+        
+        // 频率表过短：即使文本只含 A-C 也要拒绝
+        let abc = cipher.to_elements("ABCABC");
+        assert_eq!(
+            chi_squared(&abc, &[1.0, 1.0, 1.0], 26).unwrap_err(),
+            CipherError::FrequencyTableMismatch { modulus: 26, table_len: 3 }
+        );
+        
+        // 频率表过长：26 个字母对 30 项频率
+        let text = cipher.to_elements(ENGLISH);
+        assert_eq!(
+            chi_squared(&text, &[1.0; 30], 26).unwrap_err(),
+            CipherError::FrequencyTableMismatch { modulus: 26, table_len: 30 }
+        );
+
+        // 元素索引超出模数
+        assert_eq!(
+            chi_squared(&cipher.to_elements("ZZ"), &[1.0; 3], 3).unwrap_err(),
+            CipherError::CharNotInCharset('Z')
+        );
+    }
+
+    #[test]
+    fn test_index_of_coincidence_skips_out_of_range() {
+        let uppercase = StringCipher::uppercase_alpha();
+        let elements = uppercase.to_elements("AAZZ");
+
+        // Z 的索引 25 超出模数 3，只统计两个 A
+        assert_eq!(index_of_coincidence(&elements, 3), 1.0);
+        assert_eq!(index_of_coincidence(&uppercase.to_elements("ZZ"), 3), 0.0);
+        assert!(!is_likely_monoalphabetic(&uppercase.to_elements("ZZZZ"), 3));
+    }
+
+    #[test]
+    fn test_recover_key_from_pair() {
+        let cipher = StringCipher::uppercase_alpha();
+        let plaintext = cipher.to_elements("ATTACK AT DAWN");
+        let ciphertext = cipher.to_elements(&cipher.encrypt("ATTACK AT DAWN", "KEY").unwrap());
+        
+        let key = recover_key_from_pair(&plaintext, &ciphertext, &cipher, 3).unwrap();
+        assert_eq!(values(&key), "KEY");
+        
+        // 周期假设错误时位移不一致
+        assert!(recover_key_from_pair(&plaintext, &ciphertext, &cipher, 2).is_none());
+        // 长度不匹配
+        assert!(recover_key_from_pair(&plaintext[1..], &ciphertext, &cipher, 3).is_none());
+        assert!(recover_key_from_pair(&plaintext, &ciphertext, &cipher, 0).is_none());
+    }
+}
@@ -0,0 +1,183 @@
+//! 字节密码模块
+//! 
+//! 针对 256 元素字节字符集（字节值即索引）的维吉尼亚密码：
+//! 
+//! ```text
+//! E(M, K) = (M + K) mod 256
+//! D(C, K) = (C - K) mod 256
+//! ```
+//! 
+//! 即字节的环绕加/减法。启用 `simd` feature 时每次处理 32 字节
+
+use nonempty_tools::NonEmptySliceRef;
+
+/// 加密字节序列
+/// 
+/// # 示例
+/// ```
+/// use vigenere_demo::NonEmptySliceRef;
+/// use vigenere_demo::bytes::encrypt_bytes;
+/// 
+/// let key = [1u8, 2];
+/// let encrypted = encrypt_bytes(&[10, 20, 255], NonEmptySliceRef::new(&key).unwrap());
+/// assert_eq!(encrypted, [11, 22, 0]);
+/// ```
+pub fn encrypt_bytes(data: &[u8], key: NonEmptySliceRef<u8>) -> Vec<u8> {
+    #[cfg(feature = "simd")]
+    {
+        simd::process(data, key.as_slice(), |m, k| m + k, u8::wrapping_add)
+    }
+    #[cfg(not(feature = "simd"))]
+    {
+        process_scalar(data, key.as_slice(), u8::wrapping_add)
+    }
+}
+
+/// 解密字节序列
+/// 
+/// # 示例
+/// ```
+/// use vigenere_demo::NonEmptySliceRef;
+/// use vigenere_demo::bytes::decrypt_bytes;
+/// 
+/// let key = [1u8, 2];
+/// let decrypted = decrypt_bytes(&[11, 22, 0], NonEmptySliceRef::new(&key).unwrap());
+/// assert_eq!(decrypted, [10, 20, 255]);
+/// ```
+pub fn decrypt_bytes(data: &[u8], key: NonEmptySliceRef<u8>) -> Vec<u8> {
+    #[cfg(feature = "simd")]
+    {
+        simd::process(data, key.as_slice(), |c, k| c - k, u8::wrapping_sub)
+    }
+    #[cfg(not(feature = "simd"))]
+    {
+        process_scalar(data, key.as_slice(), u8::wrapping_sub)
+    }
+}
+
+/// 标量处理：逐字节应用运算，密钥循环使用
+fn process_scalar(data: &[u8], key: &[u8], operation: fn(u8, u8) -> u8) -> Vec<u8> {
+    data.iter()
+        .zip(key.iter().cycle())
+        .map(|(&b, &k)| operation(b, k))
+        .collect()
+}
+
+/// SIMD 处理（需启用 `simd` feature）
+#[cfg(feature = "simd")]
+mod simd {
+    use wide::u8x32;
+
+    /// 每个向量的字节数
+    const LANES: usize = 32;
+
+    /// 按 32 字节一组处理，不足一组的尾部回退到标量运算
+    /// 
+    /// 密钥长度不是 32 的因数时，相邻分组的密钥相位不同，
+    /// 因此预先计算 `lcm(key_len, 32) / 32` 个密钥向量并循环使用；
+    /// 向量数不超过数据的分组数，避免长密钥加密短数据时预计算大量用不到的向量。
+    /// 数据比密钥短时密钥向量无法复用，直接使用标量运算
+    pub(super) fn process<V>(data: &[u8], key: &[u8], vector_op: V, scalar_op: fn(u8, u8) -> u8) -> Vec<u8>
+    where
+        V: Fn(u8x32, u8x32) -> u8x32,
+    {
+        if data.len() < key.len() {
+            return super::process_scalar(data, key, scalar_op);
+        }
+        
+        let lanes = key_lanes(key, data.len() / LANES);
+        let mut result = Vec::with_capacity(data.len());
+        
+        let mut chunks = data.chunks_exact(LANES);
+        for (chunk, lane) in (&mut chunks).zip(lanes.iter().cycle()) {
+            let block = u8x32::new(chunk.try_into().unwrap());
+            result.extend_from_slice(&vector_op(block, *lane).to_array());
+        }
+        
+        let offset = data.len() - chunks.remainder().len();
+        result.extend(
+            chunks
+                .remainder()
+                .iter()
+                .enumerate()
+                .map(|(i, &b)| scalar_op(b, key[(offset + i) % key.len()])),
+        );
+        
+        result
+    }
+
+    /// 预计算密钥向量：第 `j` 个向量对应相位 `j * 32 mod key_len` 的密钥展开
+    /// 
+    /// 最多计算 `max_lanes` 个；不足一个完整周期时每个向量只使用一次，不会被循环复用
+    fn key_lanes(key: &[u8], max_lanes: usize) -> Vec<u8x32> {
+        let period = lcm(key.len(), LANES);
+        
+        (0..period)
+            .step_by(LANES)
+            .take(max_lanes)
+            .map(|start| {
+                let mut lane = [0u8; LANES];
+                for (j, b) in lane.iter_mut().enumerate() {
+                    *b = key[(start + j) % key.len()];
+                }
+                u8x32::new(lane)
+            })
+            .collect()
+    }
+
+    fn lcm(a: usize, b: usize) -> usize {
+        let (mut x, mut y) = (a, b);
+        while y != 0 {
+            (x, y) = (y, x % y);
+        }
+        a / x * b
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 37 + 11) as u8).collect()
+    }
+
+    #[test]
+    fn test_bytes_wrapping() {
+        let key = [200u8, 1];
+        let key = NonEmptySliceRef::new(&key).unwrap();
+        assert_eq!(encrypt_bytes(&[100, 255, 56], key), [44, 0, 0]);
+        assert_eq!(decrypt_bytes(&[44, 0, 0], key), [100, 255, 56]);
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let data = sample(1000);
+        let key = b"secret key";
+        let key = NonEmptySliceRef::new(key.as_slice()).unwrap();
+        let encrypted = encrypt_bytes(&data, key);
+        assert_ne!(encrypted, data);
+        assert_eq!(decrypt_bytes(&encrypted, key), data);
+    }
+
+    #[cfg(feature = "simd")]
+    #[test]
+    fn test_simd_matches_scalar() {
+        for key_len in 1..=70 {
+            let key = sample(key_len + 3)[3..].to_vec();
+            for data_len in [0, 1, 31, 32, 33, 64, 95, 200, 1000] {
+                let data = sample(data_len);
+                assert_eq!(
+                    simd::process(&data, &key, |m, k| m + k, u8::wrapping_add),
+                    process_scalar(&data, &key, u8::wrapping_add),
+                    "key_len={key_len}, data_len={data_len}"
+                );
+                assert_eq!(
+                    simd::process(&data, &key, |c, k| c - k, u8::wrapping_sub),
+                    process_scalar(&data, &key, u8::wrapping_sub),
+                    "key_len={key_len}, data_len={data_len}"
+                );
+            }
+        }
+    }
+}
@@ -6,6 +6,8 @@ use std::fmt::Debug;
 
 use nonempty_tools::{NonEmptySliceRef, NonEmptyVec};
 
+use crate::error::CipherError;
+
 /// 维吉尼亚密码元素 trait
 /// 
 /// 任何实现此 trait 的类型都可以作为密码系统的基本元素
@@ -30,6 +32,21 @@ pub trait CipherElement: Clone + Debug {
     fn value(&self) -> Self::Value;
 }
 
+/// 提取元素序列的值
+/// 
+/// 等价于 `elements.iter().map(|e| e.value()).collect()`
+/// 
+/// # 示例
+/// ```
+/// use vigenere_demo::{map_values, DigitElement};
+/// 
+/// let digits: Vec<DigitElement> = [4, 2].into_iter().filter_map(DigitElement::new).collect();
+/// assert_eq!(map_values(&digits), [4, 2]);
+/// ```
+pub fn map_values<T: CipherElement>(elements: &[T]) -> Vec<T::Value> {
+    elements.iter().map(|e| e.value()).collect()
+}
+
 /// 维吉尼亚密码核心结构（泛型版本）
 /// 
 /// 使用泛型 T 支持任意实现 CipherElement 的类型
@@ -65,6 +82,60 @@ impl<T: CipherElement> VigenereCipher<T> {
         Self { charset: charset.into_inner(), modulus }
     }
     
+    /// 使用普通 `Vec` 创建密码器
+    /// 
+    /// 字符集为空时返回 [`CipherError::EmptyCharset`]，省去调用方手动构造 [`NonEmptyVec`]
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::{CharElement, CipherError, VigenereCipher};
+    /// 
+    /// let charset: Vec<CharElement> = "ABC"
+    ///     .chars()
+    ///     .enumerate()
+    ///     .map(|(i, c)| CharElement::new(c, i))
+    ///     .collect();
+    /// 
+    /// let cipher = VigenereCipher::try_new(charset).unwrap();
+    /// assert_eq!(cipher.modulus(), 3);
+    /// 
+    /// let empty: Vec<CharElement> = Vec::new();
+    /// assert_eq!(VigenereCipher::try_new(empty).unwrap_err(), CipherError::EmptyCharset);
+    /// ```
+    pub fn try_new(charset: Vec<T>) -> Result<Self, CipherError> {
+        let charset = NonEmptyVec::try_from_vec(charset).map_err(|_| CipherError::EmptyCharset)?;
+        Ok(Self::new(charset))
+    }
+    
+    /// 使用自定义模数创建密码器
+    /// 
+    /// 运算只使用前 `modulus` 个元素，但字符集中的所有元素都可以作为输入，
+    /// 便于实验比字符集更小的模数
+    /// 
+    /// 索引不小于 `modulus` 的元素加密后会落入前 `modulus` 个元素之中，但这一过程不可逆：
+    /// 解密只能得到与原索引模 `modulus` 同余的元素，原元素无法恢复。
+    /// 需要往返一致时，明文应只包含前 `modulus` 个元素
+    /// 
+    /// # 参数
+    /// - `charset`: 元素集合
+    /// - `modulus`: 模数，必须在 `1..=charset.len()` 范围内
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::{VigenereCipher, DigitElement, NonEmptyVec};
+    /// 
+    /// let charset: Vec<DigitElement> = (0..10).map(|i| DigitElement::new(i).unwrap()).collect();
+    /// let cipher = VigenereCipher::with_modulus(NonEmptyVec::try_from_vec(charset).unwrap(), 5).unwrap();
+    /// assert_eq!(cipher.modulus(), 5);
+    /// ```
+    pub fn with_modulus(charset: NonEmptyVec<T>, modulus: usize) -> Result<Self, CipherError> {
+        if modulus == 0 || modulus > charset.len() {
+            return Err(CipherError::InvalidModulus { modulus, charset_len: charset.len() });
+        }
+        
+        Ok(Self { charset: charset.into_inner(), modulus })
+    }
+    
     /// 获取字符集大小（模数）
     pub fn modulus(&self) -> usize {
         self.modulus
@@ -106,7 +177,29 @@ impl<T: CipherElement> VigenereCipher<T> {
     /// # 返回
     /// 解密后的元素序列
     pub fn decrypt(&self, ciphertext: &[T], key: NonEmptySliceRef<T>) -> Vec<T> {
-        self.process(ciphertext, key.as_slice(), |c, k, n| (c + n - k) % n)
+        // 自定义模数时密钥索引可能不小于模数，先取模避免下溢
+        self.process(ciphertext, key.as_slice(), |c, k, n| (c + n - k % n) % n)
+    }
+    
+    /// 使用索引序列作为密钥加密
+    /// 
+    /// 适用于密钥不是同类型元素的场景，每个索引即该位置的位移（自动对模数取模），
+    /// 结果与使用相同索引的元素密钥调用 [`VigenereCipher::encrypt`] 一致
+    /// 
+    /// # 参数
+    /// - `input`: 明文元素序列
+    /// - `key_indices`: 密钥位移序列
+    pub fn encrypt_by_indices(&self, input: &[T], key_indices: NonEmptySliceRef<usize>) -> Vec<T> {
+        let key = key_indices.as_slice();
+        self.process_shifts(input, |i| key[i % key.len()], |m, k, n| (m + k % n) % n)
+    }
+    
+    /// 使用索引序列作为密钥解密
+    /// 
+    /// 参见 [`VigenereCipher::encrypt_by_indices`]
+    pub fn decrypt_by_indices(&self, input: &[T], key_indices: NonEmptySliceRef<usize>) -> Vec<T> {
+        let key = key_indices.as_slice();
+        self.process_shifts(input, |i| key[i % key.len()], |c, k, n| (c + n - k % n) % n)
     }
     
     /// 核心处理函数：优雅的函数式设计
@@ -119,17 +212,27 @@ impl<T: CipherElement> VigenereCipher<T> {
     where
         F: Fn(usize, usize, usize) -> usize,
     {
-        let result = input
+        self.process_shifts(input, |i| key[i % key.len()].index(), operation)
+    }
+    
+    /// 按位置取位移的通用处理函数
+    /// 
+    /// # 类型参数
+    /// - `S`: 位移函数，接受位置返回该位置的密钥索引
+    /// - `F`: 运算函数，接受 (元素索引, 密钥索引, 模数) 返回新索引
+    fn process_shifts<S, F>(&self, input: &[T], shift: S, operation: F) -> Vec<T>
+    where
+        S: Fn(usize) -> usize,
+        F: Fn(usize, usize, usize) -> usize,
+    {
+        input
             .iter()
             .enumerate()
             .map(|(i, element)| {
-                let key_element = &key[i % key.len()];
-                let new_index = operation(element.index(), key_element.index(), self.modulus);
+                let new_index = operation(element.index(), shift(i), self.modulus);
                 self.charset[new_index].clone()
             })
-            .collect();
-        
-        result
+            .collect()
     }
 }
 
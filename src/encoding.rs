@@ -0,0 +1,127 @@
+//! 文本编码辅助模块
+//! 
+//! 提供密文传输所需的轻量编码与校验实现，避免引入额外依赖
+
+/// 将字节编码为小写十六进制字符串
+pub(crate) fn hex_encode(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    
+    let mut result = String::with_capacity(bytes.len() * 2);
+    for &b in bytes {
+        result.push(DIGITS[(b >> 4) as usize] as char);
+        result.push(DIGITS[(b & 0x0f) as usize] as char);
+    }
+    result
+}
+
+/// 解码十六进制字符串（大小写均可），格式无效时返回 `None`
+pub(crate) fn hex_decode(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    
+    s.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let high = (pair[0] as char).to_digit(16)?;
+            let low = (pair[1] as char).to_digit(16)?;
+            Some((high * 16 + low) as u8)
+        })
+        .collect()
+}
+
+/// URL 安全的 Base64 字母表（RFC 4648 §5）
+const BASE64_URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// 编码为 URL 安全且不带填充的 Base64
+pub(crate) fn base64_url_encode(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |acc, (i, &b)| acc | ((b as u32) << (16 - 8 * i)));
+        // 1 字节输出 2 个字符，2 字节输出 3 个，3 字节输出 4 个
+        for i in 0..=chunk.len() {
+            result.push(BASE64_URL[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+        }
+    }
+    result
+}
+
+/// 解码 URL 安全且不带填充的 Base64，格式无效时返回 `None`
+pub(crate) fn base64_url_decode(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 4 == 1 {
+        return None;
+    }
+    
+    let mut result = Vec::with_capacity(s.len() / 4 * 3 + 2);
+    for chunk in s.as_bytes().chunks(4) {
+        let mut n = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            let value = BASE64_URL.iter().position(|&b| b == c)? as u32;
+            n |= value << (18 - 6 * i);
+        }
+        for i in 0..chunk.len() - 1 {
+            result.push((n >> (16 - 8 * i)) as u8);
+        }
+    }
+    Some(result)
+}
+
+/// 计算 CRC-32（IEEE 802.3，反射多项式 `0xEDB88320`）校验值
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &b in bytes {
+        crc ^= b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_round_trip() {
+        let bytes = [0x00, 0x0f, 0xab, 0xff];
+        assert_eq!(hex_encode(&bytes), "000fabff");
+        assert_eq!(hex_decode("000fabff").unwrap(), bytes);
+        assert_eq!(hex_decode("000FABFF").unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_hex_decode_invalid() {
+        assert!(hex_decode("abc").is_none());
+        assert!(hex_decode("zz").is_none());
+        assert!(hex_decode("+1").is_none());
+    }
+
+    #[test]
+    fn test_base64_url_round_trip() {
+        // RFC 4648 测试向量（去掉填充）
+        let vectors = [("", ""), ("f", "Zg"), ("fo", "Zm8"), ("foo", "Zm9v"), ("foobar", "Zm9vYmFy")];
+        for (plain, encoded) in vectors {
+            assert_eq!(base64_url_encode(plain.as_bytes()), encoded);
+            assert_eq!(base64_url_decode(encoded).unwrap(), plain.as_bytes());
+        }
+        
+        let bytes = [0xfb, 0xff, 0xbf];
+        assert_eq!(base64_url_encode(&bytes), "-_-_");
+        assert_eq!(base64_url_decode("-_-_").unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_base64_url_decode_invalid() {
+        assert!(base64_url_decode("Zm9vY").is_none());
+        assert!(base64_url_decode("Zm9v+A").is_none());
+        assert!(base64_url_decode("Zm9=").is_none());
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b"The quick brown fox jumps over the lazy dog"), 0x414F_A339);
+    }
+}
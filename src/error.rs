@@ -0,0 +1,110 @@
+//! 错误类型模块
+//!
+//! 定义字符串层接口使用的统一错误类型
+
+use std::fmt;
+
+/// 密码操作错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CipherError {
+    /// 字符集为空
+    EmptyCharset,
+    /// 字符集包含重复字符
+    DuplicateChar(char),
+    /// 密钥为空
+    EmptyKey,
+    /// 字符不在字符集中
+    CharNotInCharset(char),
+    /// 严格模式下密文包含字符集外的字符（附带字符位置，从 0 开始）
+    InvalidCiphertextChar { ch: char, position: usize },
+    /// 十六进制密文格式无效（长度为奇数、含非十六进制字符或解码后不是 UTF-8）
+    InvalidHex,
+    /// 配置令牌格式无效（不是 URL 安全的 Base64 或解码后不是 UTF-8）
+    InvalidToken,
+    /// 弱密钥（最小周期为 1，等价于凯撒密码）
+    WeakKey,
+    /// 加密结果与明文相同（密钥在所有字符集位置上的位移都为 0）
+    IdentityEncryption,
+    /// 置换无效（长度与字符集大小不符，或不是 `0..n` 的排列）
+    InvalidPermutation,
+    /// 模数无效（为 0 或超过字符集大小）
+    InvalidModulus { modulus: usize, charset_len: usize },
+    /// 字符范围无效（起点大于终点）
+    InvalidRange { start: char, end: char },
+    /// 进制无效（必须在 2 到 36 之间）
+    InvalidBase(usize),
+    /// 生成的字符集超过大小上限
+    CharsetTooLarge { size: usize, max: usize },
+    /// 密钥空间过大，无法穷举
+    KeyspaceTooLarge { modulus: usize, key_len: usize, max: usize },
+    /// 解密结果的校验值与预期不符（密文或密钥被篡改）
+    ChecksumMismatch { expected: u32, actual: u32 },
+    /// 解密结果未通过合理性检查
+    ImplausiblePlaintext,
+    /// 频率表长度与字符集大小不一致
+    FrequencyTableMismatch { modulus: usize, table_len: usize },
+    /// 自检失败：预设字符集的往返加解密结果不一致
+    SelfTestFailed(&'static str),
+    /// 干扰字符在字符集中（解密时无法与密文区分）
+    ChaffInCharset(char),
+    /// 干扰字符的插入概率不是有限数（NaN 或无穷大）
+    InvalidRate,
+    /// 预解析密钥不是由字符集相同的密码器创建的
+    PreparedKeyMismatch,
+    /// 流式密钥在消息结束前耗尽
+    KeyExhausted { needed: usize, available: usize },
+    /// 超过截止时间仍未完成（附带已处理的字符数）
+    Timeout { processed: usize },
+    /// 读写或解析输入输出失败（保存底层错误的描述）
+    Io(String),
+}
+
+impl fmt::Display for CipherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CipherError::EmptyCharset => write!(f, "字符集不能为空"),
+            CipherError::DuplicateChar(c) => write!(f, "字符集包含重复字符 '{}'", c),
+            CipherError::EmptyKey => write!(f, "密钥不能为空"),
+            CipherError::CharNotInCharset(c) => write!(f, "字符 '{}' 不在字符集中", c),
+            CipherError::InvalidCiphertextChar { ch, position } => {
+                write!(f, "密文第 {} 个字符 '{}' 不在字符集中", position, ch)
+            }
+            CipherError::InvalidHex => write!(f, "无效的十六进制密文"),
+            CipherError::InvalidToken => write!(f, "无效的配置令牌"),
+            CipherError::WeakKey => write!(f, "密钥过弱：所有字符相同，等价于凯撒密码"),
+            CipherError::IdentityEncryption => write!(f, "加密结果与明文相同，密钥没有起作用"),
+            CipherError::InvalidPermutation => write!(f, "置换无效，必须是 0 到字符集大小减 1 的一个排列"),
+            CipherError::InvalidModulus { modulus, charset_len } => {
+                write!(f, "模数 {} 无效，必须在 1 到字符集大小 {} 之间", modulus, charset_len)
+            }
+            CipherError::InvalidRange { start, end } => {
+                write!(f, "字符范围无效：起点 '{}' 大于终点 '{}'", start, end)
+            }
+            CipherError::InvalidBase(base) => write!(f, "进制 {} 无效，必须在 2 到 36 之间", base),
+            CipherError::CharsetTooLarge { size, max } => {
+                write!(f, "字符集过大：{} 个字符，上限为 {}", size, max)
+            }
+            CipherError::KeyspaceTooLarge { modulus, key_len, max } => {
+                write!(f, "密钥空间过大：{}^{} 超过穷举上限 {}", modulus, key_len, max)
+            }
+            CipherError::ChecksumMismatch { expected, actual } => {
+                write!(f, "校验失败：预期 {:08x}，实际 {:08x}", expected, actual)
+            }
+            CipherError::ImplausiblePlaintext => write!(f, "解密结果未通过合理性检查，密钥可能错误"),
+            CipherError::FrequencyTableMismatch { modulus, table_len } => {
+                write!(f, "频率表长度 {} 与字符集大小 {} 不一致", table_len, modulus)
+            }
+            CipherError::SelfTestFailed(preset) => write!(f, "自检失败：预设字符集 {} 往返加解密结果不一致", preset),
+            CipherError::ChaffInCharset(c) => write!(f, "干扰字符 '{}' 在字符集中，无法与密文区分", c),
+            CipherError::InvalidRate => write!(f, "干扰字符的插入概率必须是有限数"),
+            CipherError::PreparedKeyMismatch => write!(f, "预解析密钥来自字符集不同的密码器"),
+            CipherError::KeyExhausted { needed, available } => {
+                write!(f, "密钥不足：需要 {} 个，只有 {} 个", needed, available)
+            }
+            CipherError::Timeout { processed } => write!(f, "超过截止时间，已处理 {} 个字符", processed),
+            CipherError::Io(message) => write!(f, "I/O 错误：{}", message),
+        }
+    }
+}
+
+impl std::error::Error for CipherError {}
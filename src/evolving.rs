@@ -0,0 +1,116 @@
+//! 密钥演化密码模块
+//! 
+//! 每加密一条消息后按固定规则推导出下一把密钥，
+//! 用于演示简单的"前向保密"思路：后续消息的密钥各不相同
+
+use crate::{CharElement, CipherElement, CipherError, StringCipher};
+
+/// 每条消息使用不同密钥的有状态密码器
+/// 
+/// 每次 [`EvolvingCipher::encrypt_message`] 或 [`EvolvingCipher::decrypt_message`] 之后，
+/// 密钥循环左移一位，并把每个元素的索引加 1（对模数取模）。
+/// 解密方必须从相同的种子密钥开始，按消息顺序逐条解密
+/// 
+/// # 示例
+/// ```
+/// use vigenere_demo::{EvolvingCipher, StringCipher};
+/// 
+/// let mut sender = EvolvingCipher::new(StringCipher::uppercase_alpha(), "KEY").unwrap();
+/// let mut receiver = EvolvingCipher::new(StringCipher::uppercase_alpha(), "KEY").unwrap();
+/// 
+/// let first = sender.encrypt_message("HELLO");
+/// let second = sender.encrypt_message("HELLO");
+/// assert_ne!(first, second);
+/// 
+/// assert_eq!(receiver.decrypt_message(&first), "HELLO");
+/// assert_eq!(receiver.decrypt_message(&second), "HELLO");
+/// ```
+#[derive(Debug)]
+pub struct EvolvingCipher {
+    cipher: StringCipher,
+    key: Vec<CharElement>,
+}
+
+impl EvolvingCipher {
+    /// 使用密码器和种子密钥创建
+    /// 
+    /// 种子密钥不能为空，且所有字符必须在字符集中
+    pub fn new(cipher: StringCipher, seed_key: &str) -> Result<Self, CipherError> {
+        let key = cipher.parse_key(seed_key)?;
+        Ok(Self { cipher, key })
+    }
+    
+    /// 获取下一条消息将使用的密钥
+    pub fn current_key(&self) -> String {
+        self.key.iter().map(|e| e.value()).collect()
+    }
+    
+    /// 使用当前密钥加密一条消息，然后演化密钥
+    pub fn encrypt_message(&mut self, plaintext: &str) -> String {
+        let result = self.cipher.encrypt_elements(plaintext, &self.key);
+        self.evolve();
+        result
+    }
+    
+    /// 使用当前密钥解密一条消息，然后演化密钥
+    pub fn decrypt_message(&mut self, ciphertext: &str) -> String {
+        let result = self.cipher.decrypt_elements(ciphertext, &self.key);
+        self.evolve();
+        result
+    }
+    
+    /// 推导下一把密钥：循环左移一位，每个索引加 1
+    fn evolve(&mut self) {
+        let n = self.cipher.modulus;
+        self.key.rotate_left(1);
+        for elem in &mut self.key {
+            *elem = self.cipher.charset[(elem.index() + 1) % n].clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evolving_in_order() {
+        let messages = ["ATTACKATDAWN", "RETREATATDUSK", "HOLDTHELINE"];
+        let mut sender = EvolvingCipher::new(StringCipher::uppercase_alpha(), "KEY").unwrap();
+        let mut receiver = EvolvingCipher::new(StringCipher::uppercase_alpha(), "KEY").unwrap();
+        
+        let encrypted: Vec<String> = messages.iter().map(|m| sender.encrypt_message(m)).collect();
+        
+        // 第一条消息使用种子密钥，之后的密钥依次演化
+        let plain = StringCipher::uppercase_alpha();
+        assert_eq!(encrypted[0], plain.encrypt(messages[0], "KEY").unwrap());
+        assert_eq!(encrypted[1], plain.encrypt(messages[1], "FZL").unwrap());
+        assert_eq!(sender.current_key(), "NHB");
+        
+        for (ciphertext, message) in encrypted.iter().zip(messages) {
+            assert_eq!(receiver.decrypt_message(ciphertext), message);
+        }
+    }
+
+    #[test]
+    fn test_evolving_out_of_order() {
+        let mut sender = EvolvingCipher::new(StringCipher::uppercase_alpha(), "KEY").unwrap();
+        let encrypted: Vec<String> = ["FIRSTMESSAGE", "SECONDMESSAGE", "THIRDMESSAGE"]
+            .iter()
+            .map(|m| sender.encrypt_message(m))
+            .collect();
+        
+        // 跳过第一条直接解密第二条，密钥状态不同步，得到错误明文
+        let mut receiver = EvolvingCipher::new(StringCipher::uppercase_alpha(), "KEY").unwrap();
+        assert_ne!(receiver.decrypt_message(&encrypted[1]), "SECONDMESSAGE");
+        assert_ne!(receiver.decrypt_message(&encrypted[0]), "FIRSTMESSAGE");
+    }
+
+    #[test]
+    fn test_evolving_invalid_seed() {
+        assert_eq!(
+            EvolvingCipher::new(StringCipher::uppercase_alpha(), "").unwrap_err(),
+            CipherError::EmptyKey
+        );
+    }
+}
@@ -0,0 +1,89 @@
+//! 结构体字段加密模块
+//! 
+//! 通过 [`FieldCipher`] trait 为结构体中需要保密的 `String` 字段提供统一的加密/解密入口，
+//! 返回加密后的新结构体，其他字段原样复制。目前需要手动实现
+
+use crate::{CipherError, StringCipher};
+
+/// 按字段加密/解密结构体
+/// 
+/// 实现时通常先用 [`StringCipher::with_key`] 校验一次密钥，再逐个加密需要保密的字段，
+/// 每个字段都从密钥第 0 位开始
+/// 
+/// # 示例
+/// ```
+/// use vigenere_demo::{CipherError, FieldCipher, StringCipher};
+/// 
+/// #[derive(Debug, PartialEq)]
+/// struct Contact {
+///     id: u32,
+///     name: String,
+/// }
+/// 
+/// impl FieldCipher for Contact {
+///     fn encrypt_fields(&self, cipher: &StringCipher, key: &str) -> Result<Self, CipherError> {
+///         let keyed = cipher.with_key(key)?;
+///         Ok(Contact { id: self.id, name: keyed.encrypt(&self.name)? })
+///     }
+///     
+///     fn decrypt_fields(&self, cipher: &StringCipher, key: &str) -> Result<Self, CipherError> {
+///         let keyed = cipher.with_key(key)?;
+///         Ok(Contact { id: self.id, name: keyed.decrypt(&self.name)? })
+///     }
+/// }
+/// 
+/// let cipher = StringCipher::uppercase_alpha();
+/// let contact = Contact { id: 7, name: "ALICE".to_string() };
+/// let encrypted = contact.encrypt_fields(&cipher, "KEY").unwrap();
+/// assert_eq!(encrypted.name, "KPGMI");
+/// assert_eq!(encrypted.decrypt_fields(&cipher, "KEY").unwrap(), contact);
+/// ```
+pub trait FieldCipher: Sized {
+    /// 返回需要保密的字段已加密的副本
+    fn encrypt_fields(&self, cipher: &StringCipher, key: &str) -> Result<Self, CipherError>;
+    
+    /// 返回需要保密的字段已解密的副本
+    fn decrypt_fields(&self, cipher: &StringCipher, key: &str) -> Result<Self, CipherError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Account {
+        username: String,
+        password: String,
+    }
+
+    impl FieldCipher for Account {
+        fn encrypt_fields(&self, cipher: &StringCipher, key: &str) -> Result<Self, CipherError> {
+            let keyed = cipher.with_key(key)?;
+            Ok(Account {
+                username: keyed.encrypt(&self.username)?,
+                password: keyed.encrypt(&self.password)?,
+            })
+        }
+
+        fn decrypt_fields(&self, cipher: &StringCipher, key: &str) -> Result<Self, CipherError> {
+            let keyed = cipher.with_key(key)?;
+            Ok(Account {
+                username: keyed.decrypt(&self.username)?,
+                password: keyed.decrypt(&self.password)?,
+            })
+        }
+    }
+
+    #[test]
+    fn test_field_cipher_two_fields() {
+        let cipher = StringCipher::alphanumeric();
+        let account = Account { username: "alice".to_string(), password: "hunter2".to_string() };
+        
+        let encrypted = account.encrypt_fields(&cipher, "Key").unwrap();
+        assert_eq!(encrypted.username, cipher.encrypt("alice", "Key").unwrap());
+        assert_eq!(encrypted.password, cipher.encrypt("hunter2", "Key").unwrap());
+        assert_eq!(encrypted.decrypt_fields(&cipher, "Key").unwrap(), account);
+        
+        assert_eq!(account.encrypt_fields(&cipher, ""), Err(CipherError::EmptyKey));
+    }
+}
@@ -2,12 +2,37 @@
 //! 
 //! 提供优雅的泛型维吉尼亚密码实现，支持自定义字符集
 
+pub mod analysis;
+pub mod bytes;
 pub mod core;
+pub mod error;
+pub mod evolving;
+pub mod field;
+pub mod mixed_radix;
+pub mod transposition;
 
-pub use core::{CipherElement, VigenereCipher};
+mod encoding;
+
+pub use core::{map_values, CipherElement, VigenereCipher};
+pub use error::CipherError;
+pub use evolving::EvolvingCipher;
+pub use field::FieldCipher;
+pub use mixed_radix::MixedRadixCipher;
 
 pub use nonempty_tools::{NonEmptySliceRef, NonEmptyVec};
 
+use std::borrow::Cow;
+use std::io::{BufRead, Read};
+use std::ops::RangeInclusive;
+use std::time::Instant;
+#[cfg(feature = "cache")]
+use std::num::NonZeroUsize;
+#[cfg(feature = "cache")]
+use std::sync::{Mutex, PoisonError};
+
+#[cfg(feature = "cache")]
+use lru::LruCache;
+
 // ==================== 预定义元素类型 ====================
 
 /// 字符元素 - 基于字符集的实现
@@ -76,19 +101,232 @@ impl CipherElement for DigitElement {
     }
 }
 
+/// 比特元素 - GF(2) 上的实现（模数为 2）
+/// 
+/// 在比特字符集上，维吉尼亚加密退化为与循环密钥的异或运算
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitElement {
+    value: bool,
+}
+
+impl BitElement {
+    /// 创建新的比特元素
+    pub fn new(value: bool) -> Self {
+        Self { value }
+    }
+}
+
+impl CipherElement for BitElement {
+    type Value = bool;
+    
+    fn index(&self) -> usize {
+        self.value as usize
+    }
+    
+    fn value(&self) -> Self::Value {
+        self.value
+    }
+}
+
+/// 创建比特密码器（字符集为 `[0, 1]`）
+/// 
+/// # 示例
+/// ```
+/// use vigenere_demo::{bit_cipher, bits_to_bytes, bytes_to_bits, NonEmptySliceRef};
+/// 
+/// let cipher = bit_cipher();
+/// let key = bytes_to_bits(&[0xFF]);
+/// let encrypted = cipher.encrypt(&bytes_to_bits(&[0x0F]), NonEmptySliceRef::new(&key).unwrap());
+/// assert_eq!(bits_to_bytes(&encrypted), [0xF0]);
+/// ```
+pub fn bit_cipher() -> VigenereCipher<BitElement> {
+    let charset = NonEmptyVec::try_from_vec(vec![BitElement::new(false), BitElement::new(true)]).unwrap();
+    VigenereCipher::new(charset)
+}
+
+/// 将字节转换为比特元素序列（每个字节高位在前）
+pub fn bytes_to_bits(bytes: &[u8]) -> Vec<BitElement> {
+    bytes
+        .iter()
+        .flat_map(|&b| (0..8).rev().map(move |i| BitElement::new((b >> i) & 1 == 1)))
+        .collect()
+}
+
+/// 将比特元素序列转换为字节（每 8 个比特一组，高位在前）
+/// 
+/// 比特数不是 8 的倍数时，最后一个字节的低位补 0
+pub fn bits_to_bytes(bits: &[BitElement]) -> Vec<u8> {
+    bits.chunks(8)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .fold(0u8, |byte, (i, bit)| byte | ((bit.value as u8) << (7 - i)))
+        })
+        .collect()
+}
+
+/// 将数字元素序列（高位在前）转换为整数
+/// 
+/// 序列为空或结果超出 `u64` 范围时返回 `None`
+/// 
+/// # 示例
+/// ```
+/// use vigenere_demo::{digits_to_u64, u64_to_digits};
+/// 
+/// assert_eq!(digits_to_u64(&u64_to_digits(12345)), Some(12345));
+/// ```
+pub fn digits_to_u64(digits: &[DigitElement]) -> Option<u64> {
+    if digits.is_empty() {
+        return None;
+    }
+    
+    digits.iter().try_fold(0u64, |acc, digit| {
+        acc.checked_mul(10)?.checked_add(digit.value() as u64)
+    })
+}
+
+/// 将整数转换为数字元素序列（高位在前）
+pub fn u64_to_digits(n: u64) -> Vec<DigitElement> {
+    n.to_string()
+        .bytes()
+        .map(|b| DigitElement { value: b - b'0' })
+        .collect()
+}
+
+/// 十进制数字密码器
+/// 
+/// 在普通逐位维吉尼亚之外，提供带进位的变体：把数字串视为一个整数，
+/// 与循环扩展后的密钥按竖式加法逐位相加（从最低位开始进位），结果对 `10^n` 取模
+#[derive(Debug, Clone, Default)]
+pub struct DigitCipher;
+
+impl DigitCipher {
+    /// 创建数字密码器
+    pub fn new() -> Self {
+        Self
+    }
+    
+    /// 带进位加密
+    /// 
+    /// 密钥从左侧对齐并循环扩展到与 `digits` 等长，然后从最右一位开始逐位相加，
+    /// 进位传递给左边一位，最高位的进位丢弃，因此输出长度与输入相同。
+    /// `digits` 和 `key` 只能包含 `0`-`9`，否则返回 [`CipherError::CharNotInCharset`]
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::DigitCipher;
+    /// 
+    /// let cipher = DigitCipher::new();
+    /// // 19 + 01 = 20：个位 9 + 1 产生进位
+    /// assert_eq!(cipher.encrypt_with_carry("19", "01").unwrap(), "20");
+    /// assert_eq!(cipher.decrypt_with_carry("20", "01").unwrap(), "19");
+    /// ```
+    pub fn encrypt_with_carry(&self, digits: &str, key: &str) -> Result<String, CipherError> {
+        self.process_with_carry(digits, key, |d, k, carry| {
+            let sum = d + k + carry;
+            (sum % 10, sum / 10)
+        })
+    }
+    
+    /// 带借位解密
+    /// 
+    /// 参见 [`DigitCipher::encrypt_with_carry`]，逐位相减并向左借位
+    pub fn decrypt_with_carry(&self, digits: &str, key: &str) -> Result<String, CipherError> {
+        self.process_with_carry(digits, key, |d, k, borrow| {
+            let diff = d + 10 - k - borrow;
+            (diff % 10, usize::from(diff < 10))
+        })
+    }
+    
+    /// 从最低位开始逐位处理
+    /// 
+    /// # 类型参数
+    /// - `F`: 运算函数，接受 (数字, 密钥数字, 进位) 返回 (新数字, 新进位)
+    fn process_with_carry<F>(&self, digits: &str, key: &str, operation: F) -> Result<String, CipherError>
+    where
+        F: Fn(usize, usize, usize) -> (usize, usize),
+    {
+        let parse = |s: &str| -> Result<Vec<usize>, CipherError> {
+            s.chars()
+                .map(|c| c.to_digit(10).map(|d| d as usize).ok_or(CipherError::CharNotInCharset(c)))
+                .collect()
+        };
+        
+        let key = parse(key)?;
+        if key.is_empty() {
+            return Err(CipherError::EmptyKey);
+        }
+        let digits = parse(digits)?;
+        
+        let mut result = vec!['0'; digits.len()];
+        let mut carry = 0;
+        for i in (0..digits.len()).rev() {
+            let (digit, next_carry) = operation(digits[i], key[i % key.len()], carry);
+            result[i] = char::from_digit(digit as u32, 10).unwrap();
+            carry = next_carry;
+        }
+        
+        Ok(result.into_iter().collect())
+    }
+}
+
+/// 为无字段枚举实现 [`CipherElement`]
+/// 
+/// 按宏中列出的变体顺序分配索引（第一个为 0），元素的值即枚举本身。
+/// 枚举需要实现 `Clone`、`Debug` 与 `PartialEq`
+/// 
+/// # 示例
+/// ```
+/// use vigenere_demo::{impl_cipher_element, CipherElement};
+/// 
+/// #[derive(Debug, Clone, PartialEq)]
+/// enum Base { A, C, G, T }
+/// 
+/// impl_cipher_element!(Base { A, C, G, T });
+/// 
+/// assert_eq!(Base::G.index(), 2);
+/// assert_eq!(Base::T.value(), Base::T);
+/// ```
+#[macro_export]
+macro_rules! impl_cipher_element {
+    ($name:ident { $($variant:ident),+ $(,)? }) => {
+        impl $crate::CipherElement for $name {
+            type Value = $name;
+            
+            fn index(&self) -> usize {
+                const ORDER: &[$name] = &[$($name::$variant),+];
+                ORDER.iter().position(|variant| variant == self).unwrap()
+            }
+            
+            fn value(&self) -> Self::Value {
+                self.clone()
+            }
+        }
+    };
+}
+
 // ==================== 便捷的字符串接口 ====================
 
 /// 字符串密码器 - 对字符集密码器的便捷封装
 /// 
 /// 提供友好的字符串加密/解密接口
+#[derive(Debug)]
 pub struct StringCipher {
     charset: Vec<CharElement>,
     modulus: usize,
+    /// 字符集与模数的 CRC32 指纹，用于快速校验 [`PreparedKey`] 的来源
+    fingerprint: u32,
+    /// 已解析密钥的 LRU 缓存，由 [`StringCipher::with_key_cache`] 开启
+    #[cfg(feature = "cache")]
+    key_cache: Option<Mutex<LruCache<String, Vec<CharElement>>>>,
 }
 
 impl StringCipher {
     /// 从字符串创建密码器
     /// 
+    /// 启用 `unicode-normalization` feature 时，字符集会先规范化为 NFC
+    /// 
     /// # 参数
     /// - `charset`: 字符集字符串，不能为空且不能包含重复字符
     /// 
@@ -98,17 +336,37 @@ impl StringCipher {
     /// 
     /// let cipher = StringCipher::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ").unwrap();
     /// ```
-    pub fn new(charset: &str) -> Result<Self, String> {
+    pub fn new(charset: &str) -> Result<Self, CipherError> {
+        Self::from_chars(charset.chars())
+    }
+    
+    /// 从字符迭代器创建密码器
+    /// 
+    /// 适用于以编程方式拼装字符集的场景，校验规则与 [`StringCipher::new`] 相同
+    /// 
+    /// # 参数
+    /// - `chars`: 字符集字符，不能为空且不能包含重复字符
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::from_chars('a'..='f').unwrap();
+    /// assert_eq!(cipher.modulus(), 6);
+    /// ```
+    pub fn from_chars(chars: impl IntoIterator<Item = char>) -> Result<Self, CipherError> {
+        let charset: String = chars.into_iter().collect();
         if charset.is_empty() {
-            return Err("字符集不能为空".to_string());
+            return Err(CipherError::EmptyCharset);
         }
         
-        let chars: Vec<char> = charset.chars().collect();
+        let normalized = normalize(&charset);
+        let chars: Vec<char> = normalized.chars().collect();
         
         // 检查重复
-        let unique: std::collections::HashSet<_> = chars.iter().collect();
-        if unique.len() != chars.len() {
-            return Err("字符集包含重复字符".to_string());
+        let mut seen = std::collections::HashSet::new();
+        if let Some(&dup) = chars.iter().find(|c| !seen.insert(**c)) {
+            return Err(CipherError::DuplicateChar(dup));
         }
         
         let charset: Vec<CharElement> = chars
@@ -118,7 +376,141 @@ impl StringCipher {
             .collect();
         
         let modulus = charset.len();
-        Ok(Self { charset, modulus })
+        let fingerprint = encoding::crc32(&[normalized.as_bytes(), &modulus.to_le_bytes()].concat());
+        Ok(Self {
+            charset,
+            modulus,
+            fingerprint,
+            #[cfg(feature = "cache")]
+            key_cache: None,
+        })
+    }
+    
+    /// 开启已解析密钥的缓存（需启用 `cache` feature）
+    /// 
+    /// 使用线程安全的 LRU 缓存最近 `capacity` 个密钥的解析结果，
+    /// 重复使用同一密钥时跳过解析。`capacity` 为 0 时关闭缓存。
+    /// 加密/解密的接口与结果均不受影响
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha().with_key_cache(64);
+    /// assert_eq!(cipher.encrypt("HELLO", "KEY").unwrap(), "RIJVS");
+    /// ```
+    #[cfg(feature = "cache")]
+    pub fn with_key_cache(mut self, capacity: usize) -> Self {
+        self.key_cache = NonZeroUsize::new(capacity).map(|cap| Mutex::new(LruCache::new(cap)));
+        self
+    }
+    
+    /// 创建密码器并报告大小写冲突
+    /// 
+    /// 字符集区分大小写，`"Aa"` 中的 `A` 与 `a` 是两个不同的元素。
+    /// 此构造函数行为与 [`StringCipher::new`] 相同，另外按字符集顺序返回
+    /// 所有与其他成员仅大小写不同的字符，帮助发现误以为“不区分大小写”的配置
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let (_, conflicts) = StringCipher::new_warn_case("Aab").unwrap();
+    /// assert_eq!(conflicts, ['A', 'a']);
+    /// ```
+    pub fn new_warn_case(charset: &str) -> Result<(Self, Vec<char>), CipherError> {
+        let cipher = Self::new(charset)?;
+        
+        let lowered: Vec<String> = cipher.charset.iter().map(|e| e.value().to_lowercase().collect()).collect();
+        let conflicts = cipher
+            .charset
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| lowered.iter().enumerate().any(|(j, other)| j != i && *other == lowered[i]))
+            .map(|(_, elem)| elem.value())
+            .collect();
+        
+        Ok((cipher, conflicts))
+    }
+    
+    /// 使用显式置换指定每个字符的索引
+    /// 
+    /// `perm[i]` 为 `charset` 中第 `i` 个字符的新索引，必须是 `0..n` 的一个排列，
+    /// 否则返回 [`CipherError::InvalidPermutation`]。这是关键词字母表的一般化形式
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// // 'A' → 2, 'B' → 0, 'C' → 1，即字符集顺序为 "BCA"
+    /// let cipher = StringCipher::with_permutation("ABC", &[2, 0, 1]).unwrap();
+    /// assert_eq!(cipher.charset_info(), StringCipher::new("BCA").unwrap().charset_info());
+    /// ```
+    pub fn with_permutation(charset: &str, perm: &[usize]) -> Result<Self, CipherError> {
+        let base = Self::new(charset)?;
+        if perm.len() != base.modulus {
+            return Err(CipherError::InvalidPermutation);
+        }
+        
+        let mut slots: Vec<Option<char>> = vec![None; base.modulus];
+        for (elem, &target) in base.charset.iter().zip(perm) {
+            match slots.get_mut(target) {
+                Some(slot @ None) => *slot = Some(elem.value()),
+                _ => return Err(CipherError::InvalidPermutation),
+            }
+        }
+        
+        Self::from_chars(slots.into_iter().flatten())
+    }
+    
+    /// 找出字符集字符串中的重复字符及其位置
+    /// 
+    /// 按首次出现的顺序返回每个重复字符和它出现的所有字符位置（从 0 开始，
+    /// 按 [`StringCipher::new`] 相同的规范化后计数），便于修正很长的自定义字符集
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// assert_eq!(StringCipher::find_duplicates("ABCA B"), [('A', vec![0, 3]), ('B', vec![1, 5])]);
+    /// assert!(StringCipher::find_duplicates("ABC").is_empty());
+    /// ```
+    pub fn find_duplicates(charset: &str) -> Vec<(char, Vec<usize>)> {
+        let mut positions: Vec<(char, Vec<usize>)> = Vec::new();
+        for (pos, c) in normalize(charset).chars().enumerate() {
+            match positions.iter_mut().find(|(seen, _)| *seen == c) {
+                Some((_, list)) => list.push(pos),
+                None => positions.push((c, vec![pos])),
+            }
+        }
+        
+        positions.retain(|(_, list)| list.len() > 1);
+        positions
+    }
+    
+    /// 由种子确定性打乱基础字符集，创建"秘密字母表"密码器（需启用 `rand` feature）
+    /// 
+    /// 先按 [`StringCipher::new`] 校验 `base`，再用种子初始化的随机数生成器打乱字符顺序。
+    /// 相同的 `base` 和 `seed` 总是得到相同的字母表，双方只需共享种子即可复现
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let a = StringCipher::shuffled("ABCDEFGHIJKLMNOPQRSTUVWXYZ", 42).unwrap();
+    /// let b = StringCipher::shuffled("ABCDEFGHIJKLMNOPQRSTUVWXYZ", 42).unwrap();
+    /// assert_eq!(a.charset_info(), b.charset_info());
+    /// ```
+    #[cfg(feature = "rand")]
+    pub fn shuffled(base: &str, seed: u64) -> Result<Self, CipherError> {
+        use rand::rngs::StdRng;
+        use rand::seq::SliceRandom;
+        use rand::SeedableRng;
+        
+        let base = Self::new(base)?;
+        let mut chars: Vec<char> = base.charset.iter().map(|elem| elem.value()).collect();
+        chars.shuffle(&mut StdRng::seed_from_u64(seed));
+        Self::from_chars(chars)
     }
     
     /// 预定义：大写英文字母 (A-Z)
@@ -147,21 +539,147 @@ impl StringCipher {
         Self::new(&chars).unwrap()
     }
     
+    /// 列出所有预定义字符集
+    /// 
+    /// 返回 `(名称, 显示标签, 密码器)` 列表：名称与对应构造函数同名，
+    /// 标签为面向用户的中文描述，便于命令行或图形界面遍历而无需重复维护预设列表
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let presets = StringCipher::presets();
+    /// assert_eq!(presets[0].0, "uppercase_alpha");
+    /// assert_eq!(presets[0].1, "大写英文字母 (A-Z)");
+    /// ```
+    pub fn presets() -> Vec<(&'static str, &'static str, StringCipher)> {
+        vec![
+            ("uppercase_alpha", "大写英文字母 (A-Z)", Self::uppercase_alpha()),
+            ("lowercase_alpha", "小写英文字母 (a-z)", Self::lowercase_alpha()),
+            ("mixed_alpha", "大小写英文字母", Self::mixed_alpha()),
+            ("alphanumeric", "字母+数字", Self::alphanumeric()),
+            ("printable_ascii", "可打印ASCII字符", Self::printable_ascii()),
+        ]
+    }
+    
+    /// 库自检：对每个预设字符集做一次往返加解密
+    /// 
+    /// 使用固定的样本文本和由字符集派生的固定密钥，检查加密确实改变了文本且解密后还原，
+    /// 返回第一个失败的预设对应的 [`CipherError::SelfTestFailed`]
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// assert!(StringCipher::self_test().is_ok());
+    /// ```
+    pub fn self_test() -> Result<(), CipherError> {
+        const SAMPLE: &str = "The quick brown fox jumps over the lazy dog. 0123456789!";
+        
+        for (name, _, cipher) in Self::presets() {
+            let key: String = [10, 4, 24]
+                .iter()
+                .map(|&i| cipher.charset[i % cipher.modulus].value())
+                .collect();
+            let encrypted = cipher.encrypt(SAMPLE, &key)?;
+            if encrypted == SAMPLE || cipher.decrypt(&encrypted, &key)? != SAMPLE {
+                return Err(CipherError::SelfTestFailed(name));
+            }
+        }
+        
+        Ok(())
+    }
+    
     /// 将字符串解析为元素序列（严格模式）
     /// 
     /// 所有字符必须在字符集中，否则返回错误
-    fn parse_string(&self, s: &str) -> Result<Vec<CharElement>, String> {
-        s.chars()
-            .map(|c| {
-                self.charset
-                    .iter()
-                    .find(|elem| elem.value() == c)
-                    .cloned()
-                    .ok_or_else(|| format!("字符 '{}' 不在字符集中", c))
-            })
+    fn parse_string(&self, s: &str) -> Result<Vec<CharElement>, CipherError> {
+        normalize(s)
+            .chars()
+            .map(|c| self.find_element(c).cloned().ok_or(CipherError::CharNotInCharset(c)))
             .collect()
     }
     
+    /// 解析密钥：密钥不能为空，且所有字符必须在字符集中
+    /// 
+    /// 开启密钥缓存时优先从缓存读取
+    fn parse_key(&self, key: &str) -> Result<Vec<CharElement>, CipherError> {
+        if key.is_empty() {
+            return Err(CipherError::EmptyKey);
+        }
+        
+        #[cfg(feature = "cache")]
+        if let Some(cache) = &self.key_cache {
+            if let Some(parsed) = cache.lock().unwrap_or_else(PoisonError::into_inner).get(key) {
+                return Ok(parsed.clone());
+            }
+            
+            let parsed = self.parse_string(key)?;
+            cache
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .put(key.to_string(), parsed.clone());
+            return Ok(parsed);
+        }
+        
+        self.parse_string(key)
+    }
+    
+    /// 在字符集中查找字符对应的元素
+    fn find_element(&self, c: char) -> Option<&CharElement> {
+        self.charset.iter().find(|elem| elem.value() == c)
+    }
+    
+    /// 核心处理函数：只变换字符集中的字符，其他字符保持不变
+    /// 
+    /// # 类型参数
+    /// - `S`: 位移函数，接受密钥位置（已处理的字符集字符数）返回该位置的位移
+    /// - `F`: 运算函数，接受 (元素索引, 位移, 模数) 返回新索引
+    fn process<S, F>(&self, text: &str, mut shift: S, operation: F) -> String
+    where
+        S: FnMut(usize) -> usize,
+        F: Fn(usize, usize, usize) -> usize,
+    {
+        let text = normalize(text);
+        // 输出字节数通常与输入相同（字符集字符宽度不同时只是估计），预先分配避免反复扩容
+        let mut result = String::with_capacity(text.len());
+        let mut key_index = 0;
+        
+        for ch in text.chars() {
+            match self.shift_char(ch, &mut key_index, &mut shift, &operation) {
+                Some(elem) => result.push(elem.value()),
+                None => result.push(ch), // 保留不在字符集中的字符
+            }
+        }
+        
+        result
+    }
+    
+    /// 单个字符的变换步骤，所有逐字符的加解密循环都通过它处理字符集字符
+    /// 
+    /// 字符在字符集中时以 `shift(*key_index)` 为位移调用 `operation`，推进密钥位置并返回结果元素；
+    /// 否则返回 `None`，密钥位置不变，由调用方决定如何输出该字符
+    fn shift_char<S, F>(&self, ch: char, key_index: &mut usize, shift: S, operation: &F) -> Option<&CharElement>
+    where
+        S: FnOnce(usize) -> usize,
+        F: Fn(usize, usize, usize) -> usize,
+    {
+        let elem = self.find_element(ch)?;
+        let new_index = operation(elem.index(), shift(*key_index), self.modulus);
+        *key_index += 1;
+        Some(&self.charset[new_index])
+    }
+    
+    /// 使用已解析的密钥元素加密
+    fn encrypt_elements(&self, plaintext: &str, key: &[CharElement]) -> String {
+        self.process(plaintext, |i| key[i % key.len()].index(), |m, k, n| (m + k) % n)
+    }
+    
+    /// 使用已解析的密钥元素解密
+    fn decrypt_elements(&self, ciphertext: &str, key: &[CharElement]) -> String {
+        self.process(ciphertext, |i| key[i % key.len()].index(), |c, k, n| (c + n - k) % n)
+    }
+    
     /// 加密字符串
     /// 
     /// 只处理字符集中的字符，其他字符保持不变
@@ -178,28 +696,9 @@ impl StringCipher {
     /// let encrypted = cipher.encrypt("HELLO", "KEY").unwrap();
     /// assert_eq!(encrypted, "RIJVS");
     /// ```
-    pub fn encrypt(&self, plaintext: &str, key: &str) -> Result<String, String> {
-        if key.is_empty() {
-            return Err("密钥不能为空".to_string());
-        }
-        
-        let key_elements = self.parse_string(key)?;
-        
-        let mut result = String::new();
-        let mut key_index = 0;
-        
-        for ch in plaintext.chars() {
-            if let Some(elem) = self.charset.iter().find(|e| e.value() == ch) {
-                let key_elem = &key_elements[key_index % key_elements.len()];
-                let new_index = (elem.index() + key_elem.index()) % self.modulus;
-                result.push(self.charset[new_index].value());
-                key_index += 1;
-            } else {
-                result.push(ch); // 保留不在字符集中的字符
-            }
-        }
-        
-        Ok(result)
+    pub fn encrypt(&self, plaintext: &str, key: &str) -> Result<String, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        Ok(self.encrypt_elements(plaintext, &key_elements))
     }
     
     /// 解密字符串
@@ -218,99 +717,4005 @@ impl StringCipher {
     /// let decrypted = cipher.decrypt("RIJVS", "KEY").unwrap();
     /// assert_eq!(decrypted, "HELLO");
     /// ```
-    pub fn decrypt(&self, ciphertext: &str, key: &str) -> Result<String, String> {
-        if key.is_empty() {
-            return Err("密钥不能为空".to_string());
-        }
-        
-        let key_elements = self.parse_string(key)?;
-        
-        let mut result = String::new();
-        let mut key_index = 0;
+    pub fn decrypt(&self, ciphertext: &str, key: &str) -> Result<String, CipherError> {
+        // 当前默认的密钥推进方式即兼容语义，由 `decrypt_legacy` 统一实现
+        self.decrypt_legacy(ciphertext, key)
+    }
+    
+    /// 绑定密钥，得到可重复使用的 [`KeyedCipher`]
+    /// 
+    /// 密钥只在此处校验一次，之后的加密/解密无需再次解析
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// let keyed = cipher.with_key("KEY").unwrap();
+    /// assert_eq!(keyed.encrypt("HELLO").unwrap(), "RIJVS");
+    /// assert_eq!(keyed.decrypt("RIJVS").unwrap(), "HELLO");
+    /// ```
+    pub fn with_key(&self, key: &str) -> Result<KeyedCipher<'_>, CipherError> {
+        let key = self.parse_key(key)?;
+        Ok(KeyedCipher { cipher: self, key })
+    }
+    
+    /// 计算逆密钥
+    /// 
+    /// 返回的密钥在每个位置上都是原密钥的加法逆元，
+    /// 因此用它加密等价于用原密钥解密
+    /// 
+    /// # 参数
+    /// - `key`: 原密钥字符串（必须只包含字符集中的字符）
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// let inverse = cipher.inverse_for_key("KEY").unwrap();
+    /// assert_eq!(inverse, "QWC");
+    /// assert_eq!(cipher.encrypt("RIJVS", &inverse).unwrap(), "HELLO");
+    /// ```
+    pub fn inverse_for_key(&self, key: &str) -> Result<String, CipherError> {
+        let key_elements = self.parse_key(key)?;
         
-        for ch in ciphertext.chars() {
-            if let Some(elem) = self.charset.iter().find(|e| e.value() == ch) {
-                let key_elem = &key_elements[key_index % key_elements.len()];
-                let new_index = (elem.index() + self.modulus - key_elem.index()) % self.modulus;
-                result.push(self.charset[new_index].value());
-                key_index += 1;
-            } else {
-                result.push(ch); // 保留不在字符集中的字符
-            }
-        }
+        let inverse = key_elements
+            .iter()
+            .map(|elem| self.charset[(self.modulus - elem.index()) % self.modulus].value())
+            .collect();
         
-        Ok(result)
+        Ok(inverse)
     }
     
-    /// 获取字符集信息
-    pub fn charset_info(&self) -> String {
-        let chars: String = self.charset.iter().map(|e| e.value()).collect();
-        format!("字符集大小: {}, 字符: \"{}\"", self.modulus, chars)
+    /// 生成指定长度的密钥流（元素形式）
+    /// 
+    /// 将密钥循环展开为 `length` 个字符集元素，便于在元素层面核对加密过程
+    /// 
+    /// # 参数
+    /// - `key`: 密钥字符串（必须只包含字符集中的字符）
+    /// - `length`: 密钥流长度
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::{CipherElement, StringCipher};
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// let stream = cipher.keystream_elements("KEY", 5).unwrap();
+    /// let chars: String = stream.iter().map(|e| e.value()).collect();
+    /// assert_eq!(chars, "KEYKE");
+    /// ```
+    pub fn keystream_elements(&self, key: &str, length: usize) -> Result<Vec<CharElement>, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        
+        Ok(key_elements.iter().cycle().take(length).cloned().collect())
+    }
+    
+    /// 计数器模式加密（类 CTR）
+    /// 
+    /// 第 `i` 个字符集字符的位移为 `(key[i % len] + f(nonce, i)) mod n`，
+    /// 其中混合函数 `f` 为 SplitMix64 终结函数：
+    /// 
+    /// ```text
+    /// z = nonce ⊕ (i × 0x9E3779B97F4A7C15)
+    /// z = (z ⊕ (z >> 30)) × 0xBF58476D1CE4E5B9
+    /// z = (z ⊕ (z >> 27)) × 0x94D049BB133111EB
+    /// f = (z ⊕ (z >> 31)) mod n
+    /// ```
+    /// 
+    /// 相同明文在不同 `nonce` 下会得到不同密文，解密时必须使用相同的 `nonce`
+    /// 
+    /// # 参数
+    /// - `text`: 明文字符串
+    /// - `key`: 密钥字符串（必须只包含字符集中的字符）
+    /// - `nonce`: 随机数
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// let encrypted = cipher.encrypt_counter("HELLO", "KEY", 42).unwrap();
+    /// assert_eq!(cipher.decrypt_counter(&encrypted, "KEY", 42).unwrap(), "HELLO");
+    /// ```
+    pub fn encrypt_counter(&self, text: &str, key: &str, nonce: u64) -> Result<String, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        let shift = self.counter_shift(&key_elements, nonce);
+        
+        Ok(self.process(text, shift, |m, k, n| (m + k) % n))
+    }
+    
+    /// 计数器模式解密，`nonce` 必须与加密时一致
+    /// 
+    /// 参见 [`StringCipher::encrypt_counter`]
+    pub fn decrypt_counter(&self, text: &str, key: &str, nonce: u64) -> Result<String, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        let shift = self.counter_shift(&key_elements, nonce);
+        
+        Ok(self.process(text, shift, |c, k, n| (c + n - k) % n))
+    }
+    
+    /// 计数器模式的位移函数
+    fn counter_shift<'k>(&self, key: &'k [CharElement], nonce: u64) -> impl Fn(usize) -> usize + 'k {
+        let modulus = self.modulus;
+        move |i| (key[i % key.len()].index() + (mix_counter(nonce, i) % modulus as u64) as usize) % modulus
+    }
+    
+    /// 提取文本中属于字符集的字符，转换为元素序列
+    /// 
+    /// 不在字符集中的字符会被忽略，便于将带标点的密文交给 [`analysis`] 模块分析
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::{CipherElement, StringCipher};
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// let elements = cipher.to_elements("HI, BOB!");
+    /// assert_eq!(elements.len(), 5);
+    /// assert_eq!(elements[2].value(), 'B');
+    /// ```
+    pub fn to_elements(&self, text: &str) -> Vec<CharElement> {
+        normalize(text)
+            .chars()
+            .filter_map(|c| self.find_element(c).cloned())
+            .collect()
+    }
+    
+    /// 获取字符集大小（模数）
+    pub fn modulus(&self) -> usize {
+        self.modulus
+    }
+    
+    /// 加密并将密文的 UTF-8 字节编码为十六进制
+    /// 
+    /// 便于通过只支持 ASCII 的文本通道传输密文
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// let hex = cipher.encrypt_hex("HELLO", "KEY").unwrap();
+    /// assert_eq!(hex, "52494a5653"); // "RIJVS"
+    /// ```
+    pub fn encrypt_hex(&self, text: &str, key: &str) -> Result<String, CipherError> {
+        let encrypted = self.encrypt(text, key)?;
+        Ok(encoding::hex_encode(encrypted.as_bytes()))
+    }
+    
+    /// 解码十六进制密文后解密
+    /// 
+    /// 参见 [`StringCipher::encrypt_hex`]
+    pub fn decrypt_hex(&self, hex: &str, key: &str) -> Result<String, CipherError> {
+        let bytes = encoding::hex_decode(hex).ok_or(CipherError::InvalidHex)?;
+        let ciphertext = String::from_utf8(bytes).map_err(|_| CipherError::InvalidHex)?;
+        self.decrypt(&ciphertext, key)
+    }
+    
+    /// 加密并返回密钥位置映射
+    /// 
+    /// 映射中的第 `i` 项对应输出的第 `i` 个字符：字符集字符为所用密钥字符的位置，
+    /// 保留字符为 `None`。便于界面按密钥字符为密文着色
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// let (encrypted, map) = cipher.encrypt_with_map("HI!", "KEY").unwrap();
+    /// assert_eq!(encrypted, "RM!");
+    /// assert_eq!(map, [Some(0), Some(1), None]);
+    /// ```
+    pub fn encrypt_with_map(&self, text: &str, key: &str) -> Result<(String, Vec<Option<usize>>), CipherError> {
+        let key_elements = self.parse_key(key)?;
+        let text = normalize(text);
+        
+        let mut map = Vec::new();
+        let mut key_index = 0;
+        for ch in text.chars() {
+            if self.find_element(ch).is_some() {
+                map.push(Some(key_index % key_elements.len()));
+                key_index += 1;
+            } else {
+                map.push(None);
+            }
+        }
+        
+        Ok((self.encrypt_elements(&text, &key_elements), map))
+    }
+    
+    /// 渐进密钥加密（维吉尼亚 + Trithemius 组合）
+    /// 
+    /// 每完整使用一轮密钥，整体位移增加 `step`：
+    /// 
+    /// ```text
+    /// shift(i) = (key[i mod len] + step × ⌊i / len⌋) mod n
+    /// ```
+    /// 
+    /// `step = 0` 时退化为普通维吉尼亚密码
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// // 第二轮密钥 "KEY" 整体后移 1 位，相当于 "LFZ"
+    /// assert_eq!(cipher.encrypt_progressive("AAAAAA", "KEY", 1).unwrap(), "KEYLFZ");
+    /// ```
+    pub fn encrypt_progressive(&self, text: &str, key: &str, step: usize) -> Result<String, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        let shift = self.progressive_shift(&key_elements, step);
+        
+        Ok(self.process(text, shift, |m, k, n| (m + k) % n))
+    }
+    
+    /// 渐进密钥解密
+    /// 
+    /// 参见 [`StringCipher::encrypt_progressive`]
+    pub fn decrypt_progressive(&self, text: &str, key: &str, step: usize) -> Result<String, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        let shift = self.progressive_shift(&key_elements, step);
+        
+        Ok(self.process(text, shift, |c, k, n| (c + n - k) % n))
+    }
+    
+    /// 渐进密钥的位移函数
+    fn progressive_shift<'k>(&self, key: &'k [CharElement], step: usize) -> impl Fn(usize) -> usize + 'k {
+        let modulus = self.modulus;
+        move |i| {
+            let cycle = (i / key.len()) % modulus;
+            (key[i % key.len()].index() + (step % modulus) * cycle) % modulus
+        }
+    }
+    
+    /// 使用同一密钥分别加密多列数据
+    /// 
+    /// 密钥只解析一次，每一列都从密钥第 0 位重新开始，结果与逐列调用
+    /// [`StringCipher::encrypt`] 相同
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// let columns = cipher.encrypt_columns(&["HELLO", "HI"], "KEY").unwrap();
+    /// assert_eq!(columns, ["RIJVS", "RM"]);
+    /// ```
+    pub fn encrypt_columns(&self, columns: &[&str], key: &str) -> Result<Vec<String>, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        
+        Ok(columns
+            .iter()
+            .map(|column| self.encrypt_elements(column, &key_elements))
+            .collect())
+    }
+    
+    /// 判断文本的字符分布是否接近均匀（疑似已加密）
+    /// 
+    /// 计算字符集字符的重合指数并乘以模数归一化：均匀随机文本约为 1.0，
+    /// 英文等自然语言明文明显更高（大写字母表下约 1.7）。归一化值低于 1.3 时返回 `true`。
+    /// 字符集字符少于 20 个时统计不可靠，始终返回 `false`
+    /// 
+    /// 可用于提示用户是否误将密文再次加密
+    pub fn looks_random(&self, text: &str) -> bool {
+        const MIN_SAMPLE: usize = 20;
+        
+        let elements = self.to_elements(text);
+        if elements.len() < MIN_SAMPLE {
+            return false;
+        }
+        
+        let normalized = analysis::index_of_coincidence(&elements, self.modulus) * self.modulus as f64;
+        normalized < analysis::UNIFORM_THRESHOLD
+    }
+    
+    /// 将密钥分解为逐位置的凯撒位移
+    /// 
+    /// 维吉尼亚密码可以看作若干个凯撒密码轮流使用，第 `i` 项即第 `i` 个密钥字符对应的位移
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// assert_eq!(cipher.as_caesar_shifts("KEY").unwrap(), [10, 4, 24]);
+    /// ```
+    pub fn as_caesar_shifts(&self, key: &str) -> Result<Vec<usize>, CipherError> {
+        Ok(self.parse_key(key)?.iter().map(|elem| elem.index()).collect())
+    }
+    
+    /// 凯撒密码暴力破解：尝试所有单字符密钥
+    /// 
+    /// 返回全部 `modulus` 个 `(位移, 候选明文)`，不在字符集中的字符保持不变
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// let candidates = cipher.caesar_brute_force("KHOOR!");
+    /// assert_eq!(candidates.len(), 26);
+    /// assert_eq!(candidates[3], (3, "HELLO!".to_string()));
+    /// ```
+    pub fn caesar_brute_force(&self, ciphertext: &str) -> Vec<(usize, String)> {
+        (0..self.modulus)
+            .map(|shift| (shift, self.process(ciphertext, |_| shift, |c, k, n| (c + n - k) % n)))
+            .collect()
+    }
+    
+    /// 加密前拒绝弱密钥
+    /// 
+    /// 单字符密钥或所有字符都相同的密钥（最小周期为 1）会让维吉尼亚密码退化为凯撒密码，
+    /// 此时返回 [`CipherError::WeakKey`]
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::{CipherError, StringCipher};
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// assert_eq!(cipher.encrypt_strong("HELLO", "KEY").unwrap(), "RIJVS");
+    /// assert_eq!(cipher.encrypt_strong("HELLO", "AAAA"), Err(CipherError::WeakKey));
+    /// ```
+    pub fn encrypt_strong(&self, text: &str, key: &str) -> Result<String, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        if key_elements.iter().all(|elem| elem.index() == key_elements[0].index()) {
+            return Err(CipherError::WeakKey);
+        }
+        
+        Ok(self.encrypt_elements(text, &key_elements))
+    }
+    
+    /// 加密并按字符返回结构化分段
+    /// 
+    /// 每个输入字符对应一个 [`Segment`]，顺序与输入一致，便于渲染可逆的对照视图
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::{Segment, StringCipher};
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// let segments = cipher.encrypt_segments("HI!", "KEY").unwrap();
+    /// assert_eq!(segments[0], Segment::Encrypted { original: 'H', output: 'R' });
+    /// assert_eq!(segments[2], Segment::Preserved('!'));
+    /// ```
+    pub fn encrypt_segments(&self, text: &str, key: &str) -> Result<Vec<Segment>, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        let text = normalize(text);
+        let encrypted = self.encrypt_elements(&text, &key_elements);
+        
+        Ok(text
+            .chars()
+            .zip(encrypted.chars())
+            .map(|(original, output)| match self.find_element(original) {
+                Some(_) => Segment::Encrypted { original, output },
+                None => Segment::Preserved(original),
+            })
+            .collect())
+    }
+    
+    /// 大小写折叠加密
+    /// 
+    /// 当字符集只包含某个字母的一种大小写形式时，输入中另一种形式的字母会先折叠为字符集中的形式再加密，
+    /// 输出时恢复为输入的大小写。字符集同时包含两种形式时不做任何折叠
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// assert_eq!(cipher.encrypt_case_folded("Hello", "KEY").unwrap(), "Rijvs");
+    /// ```
+    pub fn encrypt_case_folded(&self, text: &str, key: &str) -> Result<String, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        let shift = |i: usize| key_elements[i % key_elements.len()].index();
+        
+        Ok(self.process_case_folded(text, shift, |m, k, n| (m + k) % n))
+    }
+    
+    /// 大小写折叠解密
+    /// 
+    /// 参见 [`StringCipher::encrypt_case_folded`]
+    pub fn decrypt_case_folded(&self, text: &str, key: &str) -> Result<String, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        let shift = |i: usize| key_elements[i % key_elements.len()].index();
+        
+        Ok(self.process_case_folded(text, shift, |c, k, n| (c + n - k) % n))
+    }
+    
+    /// 与 [`StringCipher::process`] 相同，但不在字符集中的字符会尝试折叠为字符集中的另一种大小写形式
+    fn process_case_folded<S, F>(&self, text: &str, mut shift: S, operation: F) -> String
+    where
+        S: FnMut(usize) -> usize,
+        F: Fn(usize, usize, usize) -> usize,
+    {
+        let text = normalize(text);
+        let mut result = String::with_capacity(text.len());
+        let mut key_index = 0;
+        
+        for ch in text.chars() {
+            if let Some(elem) = self.shift_char(ch, &mut key_index, &mut shift, &operation) {
+                result.push(elem.value());
+            } else if let Some(elem) =
+                swap_case(ch).and_then(|c| self.shift_char(c, &mut key_index, &mut shift, &operation))
+            {
+                result.push(to_case_of(elem.value(), ch));
+            } else {
+                result.push(ch); // 保留不在字符集中的字符
+            }
+        }
+        
+        result
+    }
+    
+    /// 计算密钥流不重复的最大消息长度
+    /// 
+    /// 超过密钥长度后密钥流开始重复，这正是短密钥加密长消息时容易被破解的原因。
+    /// 密钥为空或包含字符集外的字符时无法加密，返回 0
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// assert_eq!(cipher.safe_length_for_key("KEY"), 3);
+    /// ```
+    pub fn safe_length_for_key(&self, key: &str) -> usize {
+        self.parse_key(key).map_or(0, |key_elements| key_elements.len())
+    }
+    
+    /// 生成确定性的测试向量（需启用 `rand` feature）
+    /// 
+    /// 使用以 `seed` 初始化的随机数生成器生成 `count` 组 `(明文, 密钥, 密文)`，
+    /// 明文长度 1-32、密钥长度 1-8，均只包含字符集字符。相同的字符集与种子总是得到相同的结果，
+    /// 可导出给其他实现做兼容性校验
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// for (plaintext, key, ciphertext) in cipher.test_vectors(5, 42) {
+    ///     assert_eq!(cipher.encrypt(&plaintext, &key).unwrap(), ciphertext);
+    /// }
+    /// ```
+    #[cfg(feature = "rand")]
+    pub fn test_vectors(&self, count: usize, seed: u64) -> Vec<(String, String, String)> {
+        use rand::rngs::StdRng;
+        use rand::{RngExt, SeedableRng};
+        
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut random_elements = |max_len: usize| -> Vec<CharElement> {
+            let len = rng.random_range(1..=max_len);
+            (0..len)
+                .map(|_| self.charset[rng.random_range(0..self.modulus)].clone())
+                .collect()
+        };
+        
+        (0..count)
+            .map(|_| {
+                let plaintext: String = random_elements(32).iter().map(|e| e.value()).collect();
+                let key = random_elements(8);
+                let ciphertext = self.encrypt_elements(&plaintext, &key);
+                (plaintext, key.iter().map(|e| e.value()).collect(), ciphertext)
+            })
+            .collect()
+    }
+    
+    /// 使用已知的位移序列（而非密钥字符串）解密
+    /// 
+    /// 位移序列比消息短时循环使用，每个位移自动对模数取模。
+    /// 适用于已知部分密钥流的取证分析和部分密钥攻击
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// // "KEY" 对应的位移为 [10, 4, 24]
+    /// assert_eq!(cipher.decrypt_with_known_keystream("RIJVS", &[10, 4, 24]).unwrap(), "HELLO");
+    /// ```
+    pub fn decrypt_with_known_keystream(&self, ciphertext: &str, keystream: &[usize]) -> Result<String, CipherError> {
+        if keystream.is_empty() {
+            return Err(CipherError::EmptyKey);
+        }
+        
+        Ok(self.process(
+            ciphertext,
+            |i| keystream[i % keystream.len()],
+            |c, k, n| (c + n - k % n) % n,
+        ))
+    }
+    
+    /// 穷举指定长度的全部密钥，返回得分最高的若干个解密结果
+    /// 
+    /// 对每个密钥解密后用 `score` 打分（越高越好），按得分降序返回前 `top_n` 个
+    /// `(密钥, 明文, 得分)`，得分相同时保持密钥的字典序。得分为 NaN 的密钥会被丢弃。
+    /// 穷举过程中只保留当前最好的 `top_n` 个结果，内存占用与密钥空间大小无关。
+    /// 只适用于小字符集和短密钥：`modulus^key_len` 超过 1,000,000 时返回
+    /// [`CipherError::KeyspaceTooLarge`]，`key_len` 为 0 时返回 [`CipherError::EmptyKey`]
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::new("ABCD").unwrap();
+    /// let ciphertext = cipher.encrypt("AAAA", "CB").unwrap();
+    /// let best = cipher
+    ///     .brute_force_keys(&ciphertext, 2, |p| p.matches('A').count() as f64, 1)
+    ///     .unwrap();
+    /// assert_eq!(best[0].0, "CB");
+    /// ```
+    pub fn brute_force_keys(
+        &self,
+        ciphertext: &str,
+        key_len: usize,
+        score: impl Fn(&str) -> f64,
+        top_n: usize,
+    ) -> Result<Vec<(String, String, f64)>, CipherError> {
+        const MAX_KEYSPACE: usize = 1_000_000;
+        
+        if key_len == 0 {
+            return Err(CipherError::EmptyKey);
+        }
+        let keyspace = u32::try_from(key_len)
+            .ok()
+            .and_then(|exp| self.modulus.checked_pow(exp))
+            .filter(|&size| size <= MAX_KEYSPACE)
+            .ok_or(CipherError::KeyspaceTooLarge { modulus: self.modulus, key_len, max: MAX_KEYSPACE })?;
+        
+        let mut indices = vec![0; key_len];
+        // 按得分降序排列，长度不超过 top_n
+        let mut results: Vec<(String, String, f64)> = Vec::with_capacity(top_n.min(keyspace));
+        for _ in 0..keyspace {
+            let key: Vec<CharElement> = indices.iter().map(|&i| self.charset[i].clone()).collect();
+            let plaintext = self.decrypt_elements(ciphertext, &key);
+            let value = score(&plaintext);
+            
+            // 插在所有得分不低于它的结果之后，得分相同时先穷举到的密钥排在前面
+            let position = results.partition_point(|result| result.2 >= value);
+            if !value.is_nan() && position < top_n {
+                if results.len() == top_n {
+                    results.pop();
+                }
+                results.insert(position, (key.iter().map(|e| e.value()).collect(), plaintext, value));
+            }
+            
+            // 按字符集顺序递增到下一个密钥（最低位在末尾）
+            for digit in indices.iter_mut().rev() {
+                *digit += 1;
+                if *digit < self.modulus {
+                    break;
+                }
+                *digit = 0;
+            }
+        }
+        
+        Ok(results)
+    }
+    
+    /// 加密并附带明文的 CRC-32 校验值
+    /// 
+    /// 维吉尼亚密码本身没有完整性保护，校验值可以发现传输中的意外损坏或简单篡改。
+    /// **注意：CRC-32 不是密码学安全的消息认证码**，攻击者可以同时伪造密文和校验值。
+    /// 校验值基于规范化后的明文计算，与 [`StringCipher::decrypt_verify`] 的结果一致
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// let (ciphertext, checksum) = cipher.encrypt_with_checksum("HELLO", "KEY").unwrap();
+    /// assert_eq!(cipher.decrypt_verify(&ciphertext, "KEY", checksum).unwrap(), "HELLO");
+    /// ```
+    pub fn encrypt_with_checksum(&self, text: &str, key: &str) -> Result<(String, u32), CipherError> {
+        let key_elements = self.parse_key(key)?;
+        let text = normalize(text);
+        
+        Ok((self.encrypt_elements(&text, &key_elements), encoding::crc32(text.as_bytes())))
+    }
+    
+    /// 解密并校验明文的 CRC-32
+    /// 
+    /// 校验值不符时返回 [`CipherError::ChecksumMismatch`]，参见 [`StringCipher::encrypt_with_checksum`]
+    pub fn decrypt_verify(&self, ciphertext: &str, key: &str, checksum: u32) -> Result<String, CipherError> {
+        let plaintext = self.decrypt(ciphertext, key)?;
+        let actual = encoding::crc32(plaintext.as_bytes());
+        if actual != checksum {
+            return Err(CipherError::ChecksumMismatch { expected: checksum, actual });
+        }
+        
+        Ok(plaintext)
+    }
+    
+    /// 遍历字符集中的 `(索引, 字符)` 对
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::new("XYZ").unwrap();
+    /// let pairs: Vec<_> = cipher.iter_indexed().collect();
+    /// assert_eq!(pairs, [(0, 'X'), (1, 'Y'), (2, 'Z')]);
+    /// ```
+    pub fn iter_indexed(&self) -> impl Iterator<Item = (usize, char)> + '_ {
+        self.charset.iter().map(|elem| (elem.index(), elem.value()))
+    }
+    
+    /// 按码点取密钥位移加密，密钥字符不必在字符集中
+    /// 
+    /// 每个密钥字符的位移为 `码点 % modulus`，因此任意字符（包括 emoji）都可以作为密钥。
+    /// **安全性更弱**：码点对模数同余的字符产生相同位移，有效密钥空间并不随字符种类增加
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// // 'A' = 65 → 13, '😀' = 128512 → 20
+    /// let encrypted = cipher.encrypt_codepoint_key("HELLO", "A😀").unwrap();
+    /// assert_eq!(encrypted, "UYYFB");
+    /// assert_eq!(cipher.decrypt_codepoint_key(&encrypted, "A😀").unwrap(), "HELLO");
+    /// ```
+    pub fn encrypt_codepoint_key(&self, text: &str, key: &str) -> Result<String, CipherError> {
+        let shifts = self.codepoint_shifts(key)?;
+        Ok(self.process(text, |i| shifts[i % shifts.len()], |m, k, n| (m + k) % n))
+    }
+    
+    /// 按码点取密钥位移解密
+    /// 
+    /// 参见 [`StringCipher::encrypt_codepoint_key`]
+    pub fn decrypt_codepoint_key(&self, text: &str, key: &str) -> Result<String, CipherError> {
+        let shifts = self.codepoint_shifts(key)?;
+        Ok(self.process(text, |i| shifts[i % shifts.len()], |c, k, n| (c + n - k) % n))
+    }
+    
+    /// 将密钥的每个码点映射为位移
+    fn codepoint_shifts(&self, key: &str) -> Result<Vec<usize>, CipherError> {
+        if key.is_empty() {
+            return Err(CipherError::EmptyKey);
+        }
+        
+        Ok(normalize(key).chars().map(|c| c as usize % self.modulus).collect())
+    }
+    
+    /// 往返密钥加密：密钥位置按三角波来回移动
+    /// 
+    /// 长度为 3 的密钥依次使用第 `0, 1, 2, 1, 0, 1, 2, …` 位，首尾不重复使用。
+    /// 单字符密钥等价于普通维吉尼亚密码
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// assert_eq!(cipher.encrypt_bouncing_key("AAAAAAA", "ABC").unwrap(), "ABCBABC");
+    /// ```
+    pub fn encrypt_bouncing_key(&self, text: &str, key: &str) -> Result<String, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        let shift = bouncing_shift(&key_elements);
+        
+        Ok(self.process(text, shift, |m, k, n| (m + k) % n))
+    }
+    
+    /// 往返密钥解密
+    /// 
+    /// 参见 [`StringCipher::encrypt_bouncing_key`]
+    pub fn decrypt_bouncing_key(&self, text: &str, key: &str) -> Result<String, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        let shift = bouncing_shift(&key_elements);
+        
+        Ok(self.process(text, shift, |c, k, n| (c + n - k) % n))
+    }
+    
+    /// 自动密钥加密
+    /// 
+    /// 密钥流由引导密钥 `primer` 加上明文本身（仅字符集字符）组成，密钥流不会周期重复
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// // 密钥流为 "QUEENATTACKATDA"
+    /// let encrypted = cipher.encrypt_autokey("ATTACKATDAWN", "QUEEN").unwrap();
+    /// assert_eq!(encrypted, "QNXEPKTMDCGN");
+    /// assert_eq!(cipher.decrypt_autokey(&encrypted, "QUEEN").unwrap(), "ATTACKATDAWN");
+    /// ```
+    pub fn encrypt_autokey(&self, text: &str, primer: &str) -> Result<String, CipherError> {
+        let primer_elements = self.parse_key(primer)?;
+        let text = normalize(text);
+        let plain_elements = self.to_elements(&text);
+        let shift = |i: usize| match primer_elements.get(i) {
+            Some(elem) => elem.index(),
+            None => plain_elements[i - primer_elements.len()].index(),
+        };
+        
+        Ok(self.process(&text, shift, |m, k, n| (m + k) % n))
+    }
+    
+    /// 自动密钥解密
+    /// 
+    /// 解密出的明文字符依次追加到密钥流，参见 [`StringCipher::encrypt_autokey`]
+    pub fn decrypt_autokey(&self, text: &str, primer: &str) -> Result<String, CipherError> {
+        let mut keystream: Vec<usize> = self.parse_key(primer)?.iter().map(|e| e.index()).collect();
+        let text = normalize(text);
+        let mut result = String::with_capacity(text.len());
+        let mut key_index = 0;
+        
+        for ch in text.chars() {
+            match self.shift_char(ch, &mut key_index, |i| keystream[i], &|c, k, n| (c + n - k) % n) {
+                Some(elem) => {
+                    result.push(elem.value());
+                    keystream.push(elem.index());
+                }
+                None => result.push(ch),
+            }
+        }
+        
+        Ok(result)
+    }
+    
+    /// 自动密钥解密并检查结果是否合理
+    /// 
+    /// 引导密钥错误时解密不会报错，只会得到乱码，且错误会沿密钥流一直传播。
+    /// 解密结果未通过 `is_plausible` 时返回 [`CipherError::ImplausiblePlaintext`]
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::{CipherError, StringCipher};
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// let has_vowels = |p: &str| p.chars().filter(|c| "AEIOU".contains(*c)).count() * 5 >= p.len();
+    /// assert_eq!(cipher.decrypt_autokey_verify("QNXEPKTMDCGN", "QUEEN", has_vowels).unwrap(), "ATTACKATDAWN");
+    /// ```
+    pub fn decrypt_autokey_verify(
+        &self,
+        ciphertext: &str,
+        primer: &str,
+        is_plausible: impl Fn(&str) -> bool,
+    ) -> Result<String, CipherError> {
+        let plaintext = self.decrypt_autokey(ciphertext, primer)?;
+        if !is_plausible(&plaintext) {
+            return Err(CipherError::ImplausiblePlaintext);
+        }
+        
+        Ok(plaintext)
+    }
+    
+    /// 逐行惰性加密
+    /// 
+    /// 每一行独立加密，**密钥在每行开头重新从第 0 位开始**，结果与逐行调用
+    /// [`StringCipher::encrypt`] 相同（行尾换行符不包含在输出中）。
+    /// 密钥无效时只产生一个错误；读取失败时产生 [`CipherError::Io`] 后结束
+    /// 
+    /// # 示例
+    /// ```
+    /// use std::io::Cursor;
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// let lines: Vec<String> = cipher
+    ///     .encrypt_lines(Cursor::new("HELLO\nHI\n"), "KEY")
+    ///     .collect::<Result<_, _>>()
+    ///     .unwrap();
+    /// assert_eq!(lines, ["RIJVS", "RM"]);
+    /// ```
+    pub fn encrypt_lines<R: BufRead>(&self, reader: R, key: &str) -> impl Iterator<Item = Result<String, CipherError>> {
+        let key_elements = self.parse_key(key);
+        let mut lines = reader.lines();
+        let mut finished = false;
+        
+        std::iter::from_fn(move || {
+            if finished {
+                return None;
+            }
+            let key_elements = match &key_elements {
+                Ok(key_elements) => key_elements,
+                Err(e) => {
+                    finished = true;
+                    return Some(Err(e.clone()));
+                }
+            };
+            
+            match lines.next()? {
+                Ok(line) => Some(Ok(self.encrypt_elements(&line, key_elements))),
+                Err(e) => {
+                    finished = true;
+                    Some(Err(CipherError::Io(e.to_string())))
+                }
+            }
+        })
+    }
+    
+    /// 加密 CSV 中的指定列
+    /// 
+    /// 从 `input` 读取 CSV，把每一行第 `column` 列（从 0 开始）的单元格加密后写入 `output`，
+    /// 其他列原样输出，返回处理的行数。每个单元格独立加密，**密钥在每个单元格开头重新从第 0 位开始**。
+    /// 首行不作为表头特殊处理；列数不足的行原样输出。
+    /// 读写或 CSV 解析失败时返回 [`CipherError::Io`]
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// let mut output = Vec::new();
+    /// let rows = cipher.encrypt_csv("1,HELLO\n2,HI\n".as_bytes(), &mut output, "KEY", 1).unwrap();
+    /// assert_eq!(rows, 2);
+    /// assert_eq!(String::from_utf8(output).unwrap(), "1,RIJVS\n2,RM\n");
+    /// ```
+    #[cfg(feature = "csv")]
+    pub fn encrypt_csv<R: std::io::Read, W: std::io::Write>(
+        &self,
+        input: R,
+        output: W,
+        key: &str,
+        column: usize,
+    ) -> Result<usize, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        let io_error = |e: csv::Error| CipherError::Io(e.to_string());
+        
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(input);
+        let mut writer = csv::WriterBuilder::new().flexible(true).from_writer(output);
+        let mut rows = 0;
+        
+        for record in reader.records() {
+            let record = record.map_err(io_error)?;
+            let encrypted: csv::StringRecord = record
+                .iter()
+                .enumerate()
+                .map(|(i, field)| {
+                    if i == column {
+                        self.encrypt_elements(field, &key_elements)
+                    } else {
+                        field.to_string()
+                    }
+                })
+                .collect();
+            writer.write_record(&encrypted).map_err(io_error)?;
+            rows += 1;
+        }
+        
+        writer.flush().map_err(|e| CipherError::Io(e.to_string()))?;
+        Ok(rows)
+    }
+    
+    /// 比较两个字符集的差异
+    /// 
+    /// 用于排查为什么一种配置下的密文无法用另一种配置解密：
+    /// 只有字符完全相同且 `reordered` 为 `false` 时，两者才能互相解密
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let diff = StringCipher::new("ABC").unwrap().diff(&StringCipher::new("ACD").unwrap());
+    /// assert_eq!(diff.only_in_self, ['B']);
+    /// assert_eq!(diff.only_in_other, ['D']);
+    /// assert!(diff.reordered); // 'C' 的索引由 2 变为 1
+    /// ```
+    pub fn diff(&self, other: &StringCipher) -> CharsetDiff {
+        let only = |a: &StringCipher, b: &StringCipher| -> Vec<char> {
+            a.charset
+                .iter()
+                .map(|elem| elem.value())
+                .filter(|&c| b.find_element(c).is_none())
+                .collect()
+        };
+        let reordered = self.charset.iter().any(|elem| {
+            other
+                .find_element(elem.value())
+                .is_some_and(|theirs| theirs.index() != elem.index())
+        });
+        
+        CharsetDiff {
+            only_in_self: only(self, other),
+            only_in_other: only(other, self),
+            reordered,
+        }
+    }
+    
+    /// 加密，没有字符需要变换时借用输入而不分配
+    /// 
+    /// 输入（规范化后）不含任何字符集字符时返回 `Cow::Borrowed`，否则返回 `Cow::Owned`。
+    /// 适合处理大部分字符都原样保留的文本流水线。密钥仍会校验
+    /// 
+    /// # 示例
+    /// ```
+    /// use std::borrow::Cow;
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// assert!(matches!(cipher.encrypt_cow("123 !?", "KEY").unwrap(), Cow::Borrowed(_)));
+    /// assert_eq!(cipher.encrypt_cow("HELLO", "KEY").unwrap(), "RIJVS");
+    /// ```
+    pub fn encrypt_cow<'a>(&self, text: &'a str, key: &str) -> Result<Cow<'a, str>, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        
+        match normalize(text) {
+            Cow::Borrowed(text) if !text.chars().any(|ch| self.find_element(ch).is_some()) => {
+                Ok(Cow::Borrowed(text))
+            }
+            normalized => Ok(Cow::Owned(self.encrypt_elements(&normalized, &key_elements))),
+        }
+    }
+    
+    /// 自动破解维吉尼亚密文，返回 `(密钥, 明文)`
+    /// 
+    /// 依次执行：用 [`analysis::best_key_length`] 估计密钥长度（不超过 `max_key_len`），
+    /// 对每个密钥位置做频率分析（与 `lang_freqs` 的卡方距离最小的位移），最后解密。
+    /// `lang_freqs[i]` 为字符集第 `i` 个字母的语言频率，因此只适用于 26 字母字符集
+    /// （如 [`StringCipher::uppercase_alpha`]），其他大小返回 [`CipherError::FrequencyTableMismatch`]。
+    /// 密文越长结果越可靠，过短时可能得到错误的密钥
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::{CipherError, StringCipher};
+    /// 
+    /// let cipher = StringCipher::mixed_alpha();
+    /// assert_eq!(
+    ///     cipher.break_ciphertext("abc", &[1.0; 26], 5),
+    ///     Err(CipherError::FrequencyTableMismatch { modulus: 52, table_len: 26 })
+    /// );
+    /// ```
+    pub fn break_ciphertext(
+        &self,
+        ciphertext: &str,
+        lang_freqs: &[f64; 26],
+        max_key_len: usize,
+    ) -> Result<(String, String), CipherError> {
+        if self.modulus != lang_freqs.len() {
+            return Err(CipherError::FrequencyTableMismatch { modulus: self.modulus, table_len: lang_freqs.len() });
+        }
+        
+        let elements = self.to_elements(ciphertext);
+        let key_len = analysis::best_key_length(&elements, max_key_len);
+        let key: Vec<CharElement> = (0..key_len)
+            .map(|offset| {
+                let coset: Vec<CharElement> = elements.iter().skip(offset).step_by(key_len).cloned().collect();
+                self.charset[analysis::best_caesar_shift(&coset, lang_freqs)].clone()
+            })
+            .collect();
+        
+        Ok((key.iter().map(|e| e.value()).collect(), self.decrypt_elements(ciphertext, &key)))
+    }
+    
+    /// 加盐加密：密钥流为 `salt` 接上循环的 `key`
+    /// 
+    /// 盐只在开头消耗一次，之后密钥照常循环。盐不会写入密文，解密时必须提供相同的盐；
+    /// 盐的字符同样必须在字符集中，空盐等价于 [`StringCipher::encrypt`]
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// // 密钥流为 "XY" + "KEYKE…"
+    /// assert_eq!(cipher.encrypt_salted("AAAAA", "KEY", "XY").unwrap(), "XYKEY");
+    /// ```
+    pub fn encrypt_salted(&self, text: &str, key: &str, salt: &str) -> Result<String, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        let salt_elements = self.parse_string(salt)?;
+        let shift = salted_shift(&salt_elements, &key_elements);
+        
+        Ok(self.process(text, shift, |m, k, n| (m + k) % n))
+    }
+    
+    /// 加盐解密
+    /// 
+    /// 参见 [`StringCipher::encrypt_salted`]
+    pub fn decrypt_salted(&self, text: &str, key: &str, salt: &str) -> Result<String, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        let salt_elements = self.parse_string(salt)?;
+        let shift = salted_shift(&salt_elements, &key_elements);
+        
+        Ok(self.process(text, shift, |c, k, n| (c + n - k) % n))
+    }
+    
+    /// 将字符集收窄为与 `chars` 的交集
+    /// 
+    /// 新字符集保持原字符集中的顺序，`chars` 中不在原字符集的字符被忽略。
+    /// 交集为空时返回 [`CipherError::EmptyCharset`]。新密码器不继承密钥缓存
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha().restrict_to("ZYXA!").unwrap();
+    /// assert_eq!(cipher.charset_info(), "字符集大小: 4, 字符: \"AXYZ\"");
+    /// ```
+    pub fn restrict_to(&self, chars: &str) -> Result<StringCipher, CipherError> {
+        let chars = normalize(chars);
+        StringCipher::from_chars(
+            self.charset
+                .iter()
+                .map(|elem| elem.value())
+                .filter(|&c| chars.contains(c)),
+        )
+    }
+    
+    /// 检查密文解密时是否每个字符都来自字符集
+    /// 
+    /// 解密本身会原样保留字符集外的字符，因此它们出现在密文中通常意味着密文损坏
+    /// 或使用了不匹配的字符集。密钥无效时返回错误，否则在所有（规范化后的）
+    /// 密文字符都在字符集中时返回 `true`
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// assert!(cipher.decrypt_is_clean("RIJVS", "KEY").unwrap());
+    /// assert!(!cipher.decrypt_is_clean("RIjVS", "KEY").unwrap());
+    /// ```
+    pub fn decrypt_is_clean(&self, ciphertext: &str, key: &str) -> Result<bool, CipherError> {
+        self.parse_key(key)?;
+        Ok(normalize(ciphertext).chars().all(|c| self.find_element(c).is_some()))
+    }
+    
+    /// 组合加密：先维吉尼亚加密，再对字符集字符做关键词列换位
+    /// 
+    /// 换位只作用于字符集字符组成的序列，换位后按原来的位置依次填回；
+    /// 不在字符集中的字符（空格、标点等）留在原位。`transpose_key` 可以包含任意字符，
+    /// 列按其字符顺序读出，为空时返回 [`CipherError::EmptyKey`]
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// let encrypted = cipher.encrypt_vigenere_then_transpose("HELLO WORLD", "KEY", "BA").unwrap();
+    /// assert_eq!(cipher.decrypt_transpose_then_vigenere(&encrypted, "KEY", "BA").unwrap(), "HELLO WORLD");
+    /// ```
+    pub fn encrypt_vigenere_then_transpose(
+        &self,
+        text: &str,
+        vig_key: &str,
+        transpose_key: &str,
+    ) -> Result<String, CipherError> {
+        if transpose_key.is_empty() {
+            return Err(CipherError::EmptyKey);
+        }
+        
+        let encrypted = self.encrypt(text, vig_key)?;
+        Ok(self.rearrange_charset_chars(&encrypted, |chars| transposition::columnar_transpose(chars, transpose_key)))
+    }
+    
+    /// 组合解密：先逆换位，再维吉尼亚解密
+    /// 
+    /// 参见 [`StringCipher::encrypt_vigenere_then_transpose`]
+    pub fn decrypt_transpose_then_vigenere(
+        &self,
+        text: &str,
+        vig_key: &str,
+        transpose_key: &str,
+    ) -> Result<String, CipherError> {
+        if transpose_key.is_empty() {
+            return Err(CipherError::EmptyKey);
+        }
+        
+        let untransposed =
+            self.rearrange_charset_chars(&normalize(text), |chars| transposition::columnar_untranspose(chars, transpose_key));
+        self.decrypt(&untransposed, vig_key)
+    }
+    
+    /// 重排文本中的字符集字符，其他字符保持在原位
+    /// 
+    /// `rearrange` 接收按顺序提取出的字符集字符，返回等长的新序列
+    fn rearrange_charset_chars(&self, text: &str, rearrange: impl FnOnce(&[char]) -> Vec<char>) -> String {
+        let chars: Vec<char> = text.chars().filter(|&c| self.find_element(c).is_some()).collect();
+        let mut rearranged = rearrange(&chars).into_iter();
+        
+        text.chars()
+            .map(|c| match self.find_element(c) {
+                Some(_) => rearranged.next().unwrap_or(c),
+                None => c,
+            })
+            .collect()
+    }
+    
+    /// 生成维吉尼亚方阵（tabula recta）
+    /// 
+    /// 第 `k` 行第 `m` 列为明文 `m` 在密钥 `k` 下的密文，即字符集左移 `k` 位后的排列
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let table = StringCipher::new("ABC").unwrap().tableau();
+    /// assert_eq!(table, [['A', 'B', 'C'], ['B', 'C', 'A'], ['C', 'A', 'B']]);
+    /// ```
+    pub fn tableau(&self) -> Vec<Vec<char>> {
+        (0..self.modulus)
+            .map(|k| {
+                (0..self.modulus)
+                    .map(|m| self.charset[(m + k) % self.modulus].value())
+                    .collect()
+            })
+            .collect()
+    }
+    
+    /// 渲染适合在终端打印的维吉尼亚方阵
+    /// 
+    /// 首行为明文字符表头，每行开头为密钥字符，字符之间用空格分隔。
+    /// 字符集超过 64 个字符时只渲染前 64 行 64 列，并在末尾附加说明。
+    /// 全角字符会导致列不对齐
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let rendered = StringCipher::new("ABC").unwrap().render_tableau();
+    /// assert_eq!(rendered, "  A B C\nA A B C\nB B C A\nC C A B\n");
+    /// ```
+    pub fn render_tableau(&self) -> String {
+        const MAX_RENDERED: usize = 64;
+        
+        // 只计算需要显示的部分，避免为大字符集生成完整方阵
+        let size = self.modulus.min(MAX_RENDERED);
+        let row = |k: usize| -> String {
+            (0..size)
+                .map(|m| self.charset[(m + k) % self.modulus].value().to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        
+        let mut result = format!("  {}\n", row(0));
+        for k in 0..size {
+            result.push_str(&format!("{} {}\n", self.charset[k].value(), row(k)));
+        }
+        if self.modulus > MAX_RENDERED {
+            result.push_str(&format!("（仅显示前 {} 个字符，共 {} 个）\n", MAX_RENDERED, self.modulus));
+        }
+        
+        result
+    }
+    
+    /// 粘滞密钥的默认哨兵字符
+    pub const STICKY_SENTINEL: char = '*';
+    
+    /// 粘滞密钥加密：密钥中的哨兵字符 [`StringCipher::STICKY_SENTINEL`] 表示沿用上一位的位移
+    /// 
+    /// 参见 [`StringCipher::encrypt_sticky_with`]
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// // "B**C" 的位移序列为 1, 1, 1, 2
+    /// assert_eq!(cipher.encrypt_sticky("AAAAAAAA", "B**C").unwrap(), "BBBCBBBC");
+    /// ```
+    pub fn encrypt_sticky(&self, text: &str, key: &str) -> Result<String, CipherError> {
+        self.encrypt_sticky_with(text, key, Self::STICKY_SENTINEL)
+    }
+    
+    /// 粘滞密钥解密
+    /// 
+    /// 参见 [`StringCipher::encrypt_sticky`]
+    pub fn decrypt_sticky(&self, text: &str, key: &str) -> Result<String, CipherError> {
+        self.decrypt_sticky_with(text, key, Self::STICKY_SENTINEL)
+    }
+    
+    /// 使用自定义哨兵字符的粘滞密钥加密
+    /// 
+    /// 密钥中的 `sentinel` 表示重复上一位的位移，其他字符必须在字符集中。
+    /// 哨兵优先于字符集：即使 `sentinel` 在字符集中也按哨兵处理。
+    /// 位于密钥开头的哨兵没有上一位，位移为 0
+    pub fn encrypt_sticky_with(&self, text: &str, key: &str, sentinel: char) -> Result<String, CipherError> {
+        let shifts = self.sticky_shifts(key, sentinel)?;
+        Ok(self.process(text, |i| shifts[i % shifts.len()], |m, k, n| (m + k) % n))
+    }
+    
+    /// 使用自定义哨兵字符的粘滞密钥解密
+    /// 
+    /// 参见 [`StringCipher::encrypt_sticky_with`]
+    pub fn decrypt_sticky_with(&self, text: &str, key: &str, sentinel: char) -> Result<String, CipherError> {
+        let shifts = self.sticky_shifts(key, sentinel)?;
+        Ok(self.process(text, |i| shifts[i % shifts.len()], |c, k, n| (c + n - k) % n))
+    }
+    
+    /// 将粘滞密钥展开为位移序列
+    fn sticky_shifts(&self, key: &str, sentinel: char) -> Result<Vec<usize>, CipherError> {
+        if key.is_empty() {
+            return Err(CipherError::EmptyKey);
+        }
+        
+        let mut shifts: Vec<usize> = Vec::with_capacity(key.len());
+        for c in normalize(key).chars() {
+            let shift = if c == sentinel {
+                shifts.last().copied().unwrap_or(0)
+            } else {
+                self.find_element(c).ok_or(CipherError::CharNotInCharset(c))?.index()
+            };
+            shifts.push(shift);
+        }
+        
+        Ok(shifts)
+    }
+    
+    /// 按词重置密钥加密
+    /// 
+    /// 每遇到一段不在字符集中的字符（如空格、标点），密钥都重新从第 0 位开始，
+    /// 因此每个词的密钥对齐方式相同，这与默认的连续密钥流不同
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// assert_eq!(cipher.encrypt_per_word("AAA AA", "KEY").unwrap(), "KEY KE");
+    /// ```
+    pub fn encrypt_per_word(&self, text: &str, key: &str) -> Result<String, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        Ok(self.process_per_word(text, &key_elements, |m, k, n| (m + k) % n))
+    }
+    
+    /// 按词重置密钥解密
+    /// 
+    /// 参见 [`StringCipher::encrypt_per_word`]
+    pub fn decrypt_per_word(&self, text: &str, key: &str) -> Result<String, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        Ok(self.process_per_word(text, &key_elements, |c, k, n| (c + n - k) % n))
+    }
+    
+    /// 与 [`StringCipher::process`] 相同，但遇到字符集外的字符时密钥位置归零
+    fn process_per_word<F>(&self, text: &str, key: &[CharElement], operation: F) -> String
+    where
+        F: Fn(usize, usize, usize) -> usize,
+    {
+        let text = normalize(text);
+        let mut result = String::with_capacity(text.len());
+        let mut key_index = 0;
+        
+        let shift = |i: usize| key[i % key.len()].index();
+        
+        for ch in text.chars() {
+            match self.shift_char(ch, &mut key_index, shift, &operation) {
+                Some(elem) => result.push(elem.value()),
+                None => {
+                    result.push(ch);
+                    key_index = 0;
+                }
+            }
+        }
+        
+        result
+    }
+    
+    /// 解释字符在加解密时会如何处理
+    /// 
+    /// 用于排查字符为什么被原样保留：是确实不在字符集中，还是只是大小写不匹配
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::{CharStatus, StringCipher};
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// assert_eq!(cipher.explain_char('C'), CharStatus::InCharset(2));
+    /// assert_eq!(cipher.explain_char('c'), CharStatus::CaseMismatch('C'));
+    /// ```
+    pub fn explain_char(&self, c: char) -> CharStatus {
+        if let Some(elem) = self.find_element(c) {
+            return CharStatus::InCharset(elem.index());
+        }
+        
+        match swap_case(c).filter(|&swapped| self.find_element(swapped).is_some()) {
+            Some(swapped) => CharStatus::CaseMismatch(swapped),
+            None => CharStatus::NotInCharset,
+        }
+    }
+    
+    /// 加密并返回下一个要使用的密钥位置
+    /// 
+    /// 返回的位置为已消耗的字符集字符数对密钥长度取模，便于接续加密后续内容
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// assert_eq!(cipher.encrypt_with_consumed("HI!", "LEMON").unwrap(), ("SM!".to_string(), 2));
+    /// ```
+    pub fn encrypt_with_consumed(&self, text: &str, key: &str) -> Result<(String, usize), CipherError> {
+        let key_elements = self.parse_key(key)?;
+        let mut consumed = 0;
+        let encrypted = self.process(
+            text,
+            |i| {
+                consumed = i + 1;
+                key_elements[i % key_elements.len()].index()
+            },
+            |m, k, n| (m + k) % n,
+        );
+        
+        Ok((encrypted, consumed % key_elements.len()))
+    }
+    
+    /// 将字符集导出为紧凑的配置令牌
+    /// 
+    /// 令牌为字符集 UTF-8 字节的 URL 安全 Base64（不带填充），可以放进 URL 或命令行参数，
+    /// 用 [`StringCipher::from_token`] 还原
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let token = StringCipher::new("ABC").unwrap().to_token();
+    /// assert_eq!(token, "QUJD");
+    /// assert_eq!(StringCipher::from_token(&token).unwrap().modulus(), 3);
+    /// ```
+    pub fn to_token(&self) -> String {
+        let chars: String = self.charset.iter().map(|e| e.value()).collect();
+        encoding::base64_url_encode(chars.as_bytes())
+    }
+    
+    /// 从 [`StringCipher::to_token`] 生成的令牌重建密码器
+    /// 
+    /// 令牌格式无效时返回 [`CipherError::InvalidToken`]，解码出的字符集同样需要通过
+    /// [`StringCipher::new`] 的校验
+    pub fn from_token(token: &str) -> Result<Self, CipherError> {
+        let bytes = encoding::base64_url_decode(token).ok_or(CipherError::InvalidToken)?;
+        let charset = String::from_utf8(bytes).map_err(|_| CipherError::InvalidToken)?;
+        Self::new(&charset)
+    }
+    
+    /// 预先校验并解析密钥
+    /// 
+    /// 返回的 [`PreparedKey`] 可以传给 [`StringCipher::encrypt_prepared`] 反复使用。
+    /// 它不借用密码器，可能被传给字符集不同的密码器，因此加解密返回 `Result`
+    /// 而不是直接返回 `String`，以拒绝来源不符的密钥
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// let key = cipher.prepare_key("KEY").unwrap();
+    /// assert_eq!(cipher.encrypt_prepared("HELLO", &key).unwrap(), "RIJVS");
+    /// assert_eq!(cipher.decrypt_prepared("RIJVS", &key).unwrap(), "HELLO");
+    /// ```
+    pub fn prepare_key(&self, key: &str) -> Result<PreparedKey, CipherError> {
+        Ok(PreparedKey {
+            elements: self.parse_key(key)?,
+            fingerprint: self.fingerprint,
+        })
+    }
+    
+    /// 使用预解析的密钥加密，结果与 [`StringCipher::encrypt`] 相同
+    /// 
+    /// 密钥不是由字符集相同的密码器创建时返回 [`CipherError::PreparedKeyMismatch`]
+    pub fn encrypt_prepared(&self, text: &str, key: &PreparedKey) -> Result<String, CipherError> {
+        Ok(self.encrypt_elements(text, self.check_prepared(key)?))
+    }
+    
+    /// 使用预解析的密钥解密，结果与 [`StringCipher::decrypt`] 相同
+    /// 
+    /// 密钥不是由字符集相同的密码器创建时返回 [`CipherError::PreparedKeyMismatch`]
+    pub fn decrypt_prepared(&self, text: &str, key: &PreparedKey) -> Result<String, CipherError> {
+        Ok(self.decrypt_elements(text, self.check_prepared(key)?))
+    }
+    
+    /// 确认预解析密钥来自字符集相同的密码器，返回其密钥元素
+    /// 
+    /// 只比较构造时算好的指纹，开销与字符集大小无关
+    fn check_prepared<'k>(&self, key: &'k PreparedKey) -> Result<&'k [CharElement], CipherError> {
+        if self.fingerprint != key.fingerprint {
+            return Err(CipherError::PreparedKeyMismatch);
+        }
+        
+        Ok(&key.elements)
+    }
+    
+    /// 加密，结果与明文相同时返回错误
+    /// 
+    /// 位移为 0 的密钥字符（字符集第 0 个字符）会让对应位置保持不变；
+    /// 密文与明文完全相同时返回 [`CipherError::IdentityEncryption`]，用于发现无效的密钥。
+    /// 不含字符集字符的文本同样无法被改变，也会返回该错误
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::{CipherError, StringCipher};
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// assert_eq!(cipher.encrypt_nontrivial("HELLO", "KEY").unwrap(), "RIJVS");
+    /// assert_eq!(cipher.encrypt_nontrivial("HELLO", "AAA"), Err(CipherError::IdentityEncryption));
+    /// ```
+    pub fn encrypt_nontrivial(&self, text: &str, key: &str) -> Result<String, CipherError> {
+        let encrypted = self.encrypt(text, key)?;
+        if encrypted == normalize(text) {
+            return Err(CipherError::IdentityEncryption);
+        }
+        
+        Ok(encrypted)
+    }
+    
+    /// 按兼容语义解密：密钥只在字符集字符上推进
+    /// 
+    /// 固定当前版本 [`StringCipher::encrypt`] 的密钥推进方式：原样保留的字符不消耗密钥位置。
+    /// [`StringCipher::decrypt`] 直接委托给本方法；若将来默认改为按所有字符推进密钥
+    /// （严格按位置对齐），已有密文仍可通过本方法解密
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// // 逗号和空格不消耗密钥：H+K I+E T+Y H+K E+E R+Y E+K
+    /// assert_eq!(cipher.encrypt("HI, THERE", "KEY").unwrap(), "RM, RRIPO");
+    /// assert_eq!(cipher.decrypt_legacy("RM, RRIPO", "KEY").unwrap(), "HI, THERE");
+    /// ```
+    pub fn decrypt_legacy(&self, ciphertext: &str, key: &str) -> Result<String, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        Ok(self.decrypt_elements(ciphertext, &key_elements))
+    }
+    
+    /// 依次生成字符集的全部循环移位
+    /// 
+    /// 第 `k` 项为字符集左移 `k` 位的字符串（即维吉尼亚方阵的第 `k` 行），共 `modulus` 项，
+    /// 第 0 项为原字符集
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::new("ABC").unwrap();
+    /// let rotations: Vec<String> = cipher.rotations().collect();
+    /// assert_eq!(rotations, ["ABC", "BCA", "CAB"]);
+    /// ```
+    pub fn rotations(&self) -> impl Iterator<Item = String> + '_ {
+        (0..self.modulus).map(move |k| {
+            self.charset[k..]
+                .iter()
+                .chain(&self.charset[..k])
+                .map(|elem| elem.value())
+                .collect()
+        })
+    }
+    
+    /// 使用字节序列作为密钥加密
+    /// 
+    /// 每个密钥字节的位移为 `byte % modulus`，调用方无需先把字节密钥转换为字符集字符。
+    /// 与 [`StringCipher::encrypt_codepoint_key`] 一样，同余的字节产生相同位移
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// // 0x01 → 1, 0xff = 255 → 21
+    /// assert_eq!(cipher.encrypt_bytes_key("AAA", &[0x01, 0xff]).unwrap(), "BVB");
+    /// ```
+    pub fn encrypt_bytes_key(&self, text: &str, key: &[u8]) -> Result<String, CipherError> {
+        if key.is_empty() {
+            return Err(CipherError::EmptyKey);
+        }
+        
+        Ok(self.process(text, |i| key[i % key.len()] as usize % self.modulus, |m, k, n| (m + k) % n))
+    }
+    
+    /// 使用字节序列作为密钥解密
+    /// 
+    /// 参见 [`StringCipher::encrypt_bytes_key`]
+    pub fn decrypt_bytes_key(&self, text: &str, key: &[u8]) -> Result<String, CipherError> {
+        if key.is_empty() {
+            return Err(CipherError::EmptyKey);
+        }
+        
+        Ok(self.process(text, |i| key[i % key.len()] as usize % self.modulus, |c, k, n| (c + n - k) % n))
+    }
+    
+    /// 统计密钥在前 `text_len` 个字符集位置上留下的不动点数量
+    /// 
+    /// 密钥字符为字符集第 0 个字符时位移为 0，该位置的明文保持不变。
+    /// 不动点占比越高，密钥越弱
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// // "KAY" 的第 1 位为 'A'，10 个位置中第 1、4、7 位不变
+    /// assert_eq!(cipher.fixed_points("KAY", 10).unwrap(), 3);
+    /// ```
+    pub fn fixed_points(&self, key: &str, text_len: usize) -> Result<usize, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        let is_zero: Vec<bool> = key_elements.iter().map(|elem| elem.index() == 0).collect();
+        
+        let (cycles, remainder) = (text_len / is_zero.len(), text_len % is_zero.len());
+        let zeros = |shifts: &[bool]| shifts.iter().filter(|&&zero| zero).count();
+        Ok(cycles * zeros(&is_zero) + zeros(&is_zero[..remainder]))
+    }
+    
+    /// 合并两个字符集
+    /// 
+    /// 新字符集为当前字符集，后接 `other` 中尚未出现的字符（保持各自顺序），
+    /// 因此当前字符集中字符的索引不变。新密码器不继承密钥缓存
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let merged = StringCipher::new("ABC").unwrap().merge(&StringCipher::new("CD").unwrap()).unwrap();
+    /// assert_eq!(merged.charset_info(), "字符集大小: 4, 字符: \"ABCD\"");
+    /// ```
+    pub fn merge(&self, other: &StringCipher) -> Result<StringCipher, CipherError> {
+        let extra = other
+            .charset
+            .iter()
+            .filter(|elem| self.find_element(elem.value()).is_none());
+        
+        StringCipher::from_chars(self.charset.iter().chain(extra).map(|elem| elem.value()))
+    }
+    
+    /// 判断密钥流在这条消息中是否不会重复
+    /// 
+    /// 密钥长度不小于消息中字符集字符的数量时返回 `true`，此时每个位置都使用不同的密钥位置，
+    /// 接近一次性密码本的用法（前提是密钥本身随机且不复用）。参见 [`StringCipher::safe_length_for_key`]
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// assert!(!cipher.covers_message("SECRET", "HI THERE").unwrap());
+    /// assert!(cipher.covers_message("SECRET", "HI, YOU!").unwrap());
+    /// ```
+    pub fn covers_message(&self, key: &str, text: &str) -> Result<bool, CipherError> {
+        let key_len = self.parse_key(key)?.len();
+        Ok(key_len >= self.to_elements(text).len())
+    }
+    
+    /// 严格解密：密文中出现字符集外的字符时返回错误
+    /// 
+    /// 默认的 [`StringCipher::decrypt`] 会原样保留这些字符；本方法在第一个这样的字符处报错，
+    /// 返回 [`CipherError::InvalidCiphertextChar`]（位置按规范化后的字符计数，从 0 开始），
+    /// 用于尽早发现不可信输入中的损坏
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::{CipherError, StringCipher};
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// assert_eq!(cipher.decrypt_strict("RIJVS", "KEY").unwrap(), "HELLO");
+    /// assert_eq!(
+    ///     cipher.decrypt_strict("RIJV?S", "KEY"),
+    ///     Err(CipherError::InvalidCiphertextChar { ch: '?', position: 4 })
+    /// );
+    /// ```
+    pub fn decrypt_strict(&self, ciphertext: &str, key: &str) -> Result<String, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        let ciphertext = normalize(ciphertext);
+        if let Some((position, ch)) = ciphertext.chars().enumerate().find(|&(_, c)| self.find_element(c).is_none()) {
+            return Err(CipherError::InvalidCiphertextChar { ch, position });
+        }
+        
+        Ok(self.decrypt_elements(&ciphertext, &key_elements))
+    }
+    
+    /// 掩码预览的默认掩码字符
+    pub const MASK_CHAR: char = '*';
+    
+    /// 加密并生成掩码预览：只显示前 `reveal` 个字符集字符，其余字符集字符替换为
+    /// [`StringCipher::MASK_CHAR`]
+    /// 
+    /// 仅用于界面展示，结果无法解密；完整密文请使用 [`StringCipher::encrypt`]。
+    /// 参见 [`StringCipher::encrypt_masked_with`]
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// assert_eq!(cipher.encrypt_masked("HELLO WORLD", "KEY", 3).unwrap(), "RIJ** *****");
+    /// ```
+    pub fn encrypt_masked(&self, text: &str, key: &str, reveal: usize) -> Result<String, CipherError> {
+        self.encrypt_masked_with(text, key, reveal, Self::MASK_CHAR)
+    }
+    
+    /// 使用自定义掩码字符的掩码预览
+    /// 
+    /// 不在字符集中的字符不计数，也不替换，保留原文的分隔结构
+    pub fn encrypt_masked_with(&self, text: &str, key: &str, reveal: usize, mask: char) -> Result<String, CipherError> {
+        let encrypted = self.encrypt(text, key)?;
+        let mut shown = 0;
+        
+        Ok(encrypted
+            .chars()
+            .map(|ch| {
+                if self.find_element(ch).is_none() {
+                    ch
+                } else if shown < reveal {
+                    shown += 1;
+                    ch
+                } else {
+                    mask
+                }
+            })
+            .collect())
+    }
+    
+    /// 计算字符集对语料的覆盖率
+    /// 
+    /// 返回 `corpus`（规范化后）中属于字符集、即会被加密而非原样保留的字符所占比例，
+    /// 范围为 `0.0..=1.0`；语料为空时返回 `0.0`。用于挑选真正覆盖数据的字符集
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// assert_eq!(cipher.coverage("AB, CD"), 4.0 / 6.0);
+    /// ```
+    pub fn coverage(&self, corpus: &str) -> f64 {
+        let corpus = normalize(corpus);
+        let mut total = 0;
+        let mut covered = 0;
+        
+        for ch in corpus.chars() {
+            total += 1;
+            if self.find_element(ch).is_some() {
+                covered += 1;
+            }
+        }
+        
+        if total == 0 {
+            return 0.0;
+        }
+        covered as f64 / total as f64
+    }
+    
+    /// 自动扩展字符集后加密
+    /// 
+    /// 文本中不在字符集中的字符（包括空格和标点）按首次出现的顺序追加到字符集末尾，
+    /// 重新校验后得到新密码器，并用新密码器加密整段文本，返回 `(密文, 新密码器)`。
+    /// 原字符在新字符集中的索引不变；文本已被完全覆盖时新密码器与当前字符集相同。
+    /// 新密码器不继承密钥缓存
+    /// 
+    /// 解密时必须使用返回的新密码器，不能使用原密码器
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::new("ABC").unwrap();
+    /// let (encrypted, widened) = cipher.encrypt_auto_widen("ABD", "B").unwrap();
+    /// assert_eq!(widened.charset_info(), "字符集大小: 4, 字符: \"ABCD\"");
+    /// assert_eq!(encrypted, "BCA");
+    /// assert_eq!(widened.decrypt(&encrypted, "B").unwrap(), "ABD");
+    /// ```
+    pub fn encrypt_auto_widen(&self, text: &str, key: &str) -> Result<(String, StringCipher), CipherError> {
+        let text = normalize(text);
+        let mut extra: Vec<char> = Vec::new();
+        for ch in text.chars() {
+            if self.find_element(ch).is_none() && !extra.contains(&ch) {
+                extra.push(ch);
+            }
+        }
+        
+        let widened = StringCipher::from_chars(self.charset.iter().map(|elem| elem.value()).chain(extra))?;
+        let encrypted = widened.encrypt(&text, key)?;
+        Ok((encrypted, widened))
+    }
+    
+    /// 带截止时间的加密
+    /// 
+    /// 每处理一批字符检查一次时钟（开始前也检查一次），超过 `deadline` 时返回
+    /// [`CipherError::Timeout`]，其中 `processed` 为已处理的字符数（含原样保留的字符）。
+    /// 用于避免超大输入长时间阻塞实时系统；按时完成时结果与 [`StringCipher::encrypt`] 相同
+    /// 
+    /// # 示例
+    /// ```
+    /// use std::time::{Duration, Instant};
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// let deadline = Instant::now() + Duration::from_secs(60);
+    /// assert_eq!(cipher.encrypt_until("HELLO", "KEY", deadline).unwrap(), "RIJVS");
+    /// ```
+    pub fn encrypt_until(&self, text: &str, key: &str, deadline: Instant) -> Result<String, CipherError> {
+        /// 两次检查时钟之间处理的字符数
+        const CHECK_INTERVAL: usize = 4096;
+        
+        let key_elements = self.parse_key(key)?;
+        let shift = |i: usize| key_elements[i % key_elements.len()].index();
+        let text = normalize(text);
+        let mut result = String::with_capacity(text.len());
+        let mut key_index = 0;
+        
+        for (processed, ch) in text.chars().enumerate() {
+            if processed % CHECK_INTERVAL == 0 && Instant::now() >= deadline {
+                return Err(CipherError::Timeout { processed });
+            }
+            
+            match self.shift_char(ch, &mut key_index, shift, &|m, k, n| (m + k) % n) {
+                Some(elem) => result.push(elem.value()),
+                None => result.push(ch),
+            }
+        }
+        
+        Ok(result)
+    }
+    
+    /// 分块惰性加密
+    /// 
+    /// 每次迭代产生密文的下一段，每段最多 `chunk_size` 个字符（按全部字符计数，
+    /// `chunk_size` 为 0 时按 1 处理）。密钥位置跨块连续推进，所有块拼接后与
+    /// [`StringCipher::encrypt`] 的结果相同。加密在迭代时才进行，便于配合背压逐段写出
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// let chunks: Vec<String> = cipher.encrypt_chunked("HELLO WORLD", "KEY", 4).unwrap().collect();
+    /// assert_eq!(chunks, ["RIJV", "S UY", "VJN"]);
+    /// ```
+    pub fn encrypt_chunked(
+        &self,
+        text: &str,
+        key: &str,
+        chunk_size: usize,
+    ) -> Result<impl Iterator<Item = String>, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        let text = normalize(text);
+        let chunk_size = chunk_size.max(1);
+        let mut offset = 0;
+        let mut key_index = 0;
+        
+        Ok(std::iter::from_fn(move || {
+            let rest = &text[offset..];
+            if rest.is_empty() {
+                return None;
+            }
+            
+            let shift = |i: usize| key_elements[i % key_elements.len()].index();
+            let mut chunk = String::with_capacity(chunk_size);
+            for ch in rest.chars().take(chunk_size) {
+                offset += ch.len_utf8();
+                match self.shift_char(ch, &mut key_index, shift, &|m, k, n| (m + k) % n) {
+                    Some(elem) => chunk.push(elem.value()),
+                    None => chunk.push(ch),
+                }
+            }
+            Some(chunk)
+        }))
+    }
+    
+    /// 加密并随机插入干扰字符
+    /// 
+    /// 在每个密文字符之前以概率 `rate`（限制在 `0.0..=1.0`）插入一个从 `chaff` 中随机选取的干扰字符，
+    /// `rate` 为 NaN 或无穷大时返回 [`CipherError::InvalidRate`]。
+    /// 干扰字符必须都不在字符集中，否则返回 [`CipherError::ChaffInCharset`]，
+    /// 这样 [`StringCipher::decrypt`] 会原样保留它们且不推进密钥位置，
+    /// [`StringCipher::decrypt_strip_chaff`] 则会将其移除。`chaff` 为空时不插入任何字符。
+    /// 明文本身不应包含干扰字符，否则去除干扰时会被一并删除
+    /// 
+    /// # 示例
+    /// ```
+    /// use rand::SeedableRng;
+    /// use rand::rngs::StdRng;
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// let mut rng = StdRng::seed_from_u64(7);
+    /// let noisy = cipher.encrypt_with_chaff("HELLO", "KEY", "#%", 0.5, &mut rng).unwrap();
+    /// assert_eq!(cipher.decrypt_strip_chaff(&noisy, "KEY", "#%").unwrap(), "HELLO");
+    /// ```
+    #[cfg(feature = "rand")]
+    pub fn encrypt_with_chaff(
+        &self,
+        text: &str,
+        key: &str,
+        chaff: &str,
+        rate: f64,
+        rng: &mut impl rand::Rng,
+    ) -> Result<String, CipherError> {
+        use rand::RngExt;
+        
+        if !rate.is_finite() {
+            return Err(CipherError::InvalidRate);
+        }
+        
+        let chaff = self.parse_chaff(chaff)?;
+        let encrypted = self.encrypt(text, key)?;
+        if chaff.is_empty() {
+            return Ok(encrypted);
+        }
+        
+        let rate = rate.clamp(0.0, 1.0);
+        let mut result = String::with_capacity(encrypted.len() * 2);
+        for ch in encrypted.chars() {
+            if rng.random_bool(rate) {
+                result.push(chaff[rng.random_range(0..chaff.len())]);
+            }
+            result.push(ch);
+        }
+        
+        Ok(result)
+    }
+    
+    /// 去除干扰字符后解密
+    /// 
+    /// 先删除 `chaff` 中的所有字符，再按 [`StringCipher::decrypt`] 解密。
+    /// 参见 [`StringCipher::encrypt_with_chaff`]
+    pub fn decrypt_strip_chaff(&self, text: &str, key: &str, chaff: &str) -> Result<String, CipherError> {
+        let chaff = self.parse_chaff(chaff)?;
+        let stripped: String = normalize(text).chars().filter(|ch| !chaff.contains(ch)).collect();
+        self.decrypt(&stripped, key)
+    }
+    
+    /// 解析干扰字符：所有字符都必须不在字符集中
+    fn parse_chaff(&self, chaff: &str) -> Result<Vec<char>, CipherError> {
+        normalize(chaff)
+            .chars()
+            .map(|ch| match self.find_element(ch) {
+                Some(_) => Err(CipherError::ChaffInCharset(ch)),
+                None => Ok(ch),
+            })
+            .collect()
+    }
+    
+    /// 加密并输出 JSON 对象
+    /// 
+    /// 格式为 `{"ciphertext": "...", "charset_size": n, "key_period": m}`，
+    /// 其中 `charset_size` 为模数，`key_period` 为密钥的最小周期（如 `"ABAB"` 为 2），
+    /// 便于 HTTP 接口统一输出格式
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// assert_eq!(
+    ///     cipher.encrypt_json("HELLO", "KEY").unwrap(),
+    ///     r#"{"ciphertext":"RIJVS","charset_size":26,"key_period":3}"#
+    /// );
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn encrypt_json(&self, text: &str, key: &str) -> Result<String, CipherError> {
+        #[derive(serde::Serialize)]
+        struct Output<'a> {
+            ciphertext: &'a str,
+            charset_size: usize,
+            key_period: usize,
+        }
+        
+        let key_elements = self.parse_key(key)?;
+        let ciphertext = self.encrypt_elements(text, &key_elements);
+        let output = Output {
+            ciphertext: &ciphertext,
+            charset_size: self.modulus,
+            key_period: key_period(&key_elements),
+        };
+        
+        // 结构体只包含字符串和整数字段，序列化不会失败
+        Ok(serde_json::to_string(&output).expect("序列化 JSON 失败"))
+    }
+    
+    /// [`StringCipher::encrypt_checked`] 建议的最短密钥长度
+    pub const MIN_KEY_LEN: usize = 6;
+    
+    /// 加密并给出密钥安全提示
+    /// 
+    /// 正常返回密文，同时附带检测到的 [`Warning`] 列表（按 `ShortKey`、`KeyLenDividesTextLen`、
+    /// `RepetitiveKey` 的顺序），提示不会导致加密失败
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::{StringCipher, Warning};
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// let (encrypted, warnings) = cipher.encrypt_checked("HELLO", "KEY").unwrap();
+    /// assert_eq!(encrypted, "RIJVS");
+    /// assert_eq!(warnings, [Warning::ShortKey]);
+    /// ```
+    pub fn encrypt_checked(&self, text: &str, key: &str) -> Result<(String, Vec<Warning>), CipherError> {
+        let key_elements = self.parse_key(key)?;
+        let key_len = key_elements.len();
+        let text_len = self.to_elements(text).len();
+        
+        let mut warnings = Vec::new();
+        if key_len < Self::MIN_KEY_LEN {
+            warnings.push(Warning::ShortKey);
+        }
+        if text_len >= 2 * key_len && text_len.is_multiple_of(key_len) {
+            warnings.push(Warning::KeyLenDividesTextLen);
+        }
+        if key_period(&key_elements) < key_len {
+            warnings.push(Warning::RepetitiveKey);
+        }
+        
+        Ok((self.encrypt_elements(text, &key_elements), warnings))
+    }
+    
+    /// 计算给定密钥长度的理论密钥空间大小
+    /// 
+    /// 返回 `modulus^key_len`，超出 `u128` 范围时返回 `None`
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// assert_eq!(cipher.keyspace(2), Some(676));
+    /// ```
+    pub fn keyspace(&self, key_len: usize) -> Option<u128> {
+        (self.modulus as u128).checked_pow(u32::try_from(key_len).ok()?)
+    }
+    
+    /// 只加密满足条件的字符
+    /// 
+    /// 只有 `should_encrypt` 返回 `true` 且在字符集中的字符才会被加密并推进密钥位置，
+    /// 其他字符原样保留。例如字符集包含数字时，也可以只加密字母
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::alphanumeric();
+    /// let encrypted = cipher.encrypt_where("HI 42", "B", |c| c.is_ascii_alphabetic()).unwrap();
+    /// assert_eq!(encrypted, "IJ 42");
+    /// ```
+    pub fn encrypt_where(
+        &self,
+        text: &str,
+        key: &str,
+        should_encrypt: impl Fn(char) -> bool,
+    ) -> Result<String, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        Ok(self.process_where(text, &key_elements, should_encrypt, |m, k, n| (m + k) % n))
+    }
+    
+    /// 只解密满足条件的字符
+    /// 
+    /// 参见 [`StringCipher::encrypt_where`]，需要使用与加密时相同的条件
+    pub fn decrypt_where(
+        &self,
+        text: &str,
+        key: &str,
+        should_decrypt: impl Fn(char) -> bool,
+    ) -> Result<String, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        Ok(self.process_where(text, &key_elements, should_decrypt, |c, k, n| (c + n - k) % n))
+    }
+    
+    /// 按条件过滤后处理：不满足条件的字符与字符集外的字符一样原样保留
+    fn process_where<P, F>(&self, text: &str, key: &[CharElement], predicate: P, operation: F) -> String
+    where
+        P: Fn(char) -> bool,
+        F: Fn(usize, usize, usize) -> usize,
+    {
+        let text = normalize(text);
+        let mut result = String::with_capacity(text.len());
+        let mut key_index = 0;
+        
+        let shift = |i: usize| key[i % key.len()].index();
+        
+        for ch in text.chars() {
+            let output = if predicate(ch) {
+                self.shift_char(ch, &mut key_index, shift, &operation)
+            } else {
+                None
+            };
+            match output {
+                Some(elem) => result.push(elem.value()),
+                None => result.push(ch),
+            }
+        }
+        
+        result
+    }
+    
+    /// 返回绑定了密钥的解密闭包
+    /// 
+    /// 密钥只在这里校验一次，闭包可以反复调用，适合在热循环中或对多条密文 `map`。
+    /// 需要同时加密时参见 [`StringCipher::with_key`]
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// let decrypt = cipher.decryptor("KEY").unwrap();
+    /// assert_eq!(decrypt("RIJVS"), "HELLO");
+    /// ```
+    pub fn decryptor<'a>(&'a self, key: &str) -> Result<impl Fn(&str) -> String + use<'a>, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        Ok(move |ciphertext: &str| self.decrypt_elements(ciphertext, &key_elements))
+    }
+    
+    /// 斐波那契位移加密
+    /// 
+    /// 不使用密钥字符串，位移序列由种子 `a`、`b` 按斐波那契递推生成：
+    /// `s0 = a`，`s1 = b`，`s(i) = s(i-1) + s(i-2)`（均对模数取模），
+    /// 第 `i` 个字符集字符使用位移 `s(i)`
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// // 位移序列 1, 1, 2, 3, 5, 8
+    /// assert_eq!(cipher.encrypt_fibonacci("AAAAAA", 1, 1).unwrap(), "BBCDFI");
+    /// ```
+    pub fn encrypt_fibonacci(&self, text: &str, a: usize, b: usize) -> Result<String, CipherError> {
+        Ok(self.process(text, self.fibonacci_shifts(a, b), |m, k, n| (m + k) % n))
+    }
+    
+    /// 斐波那契位移解密
+    /// 
+    /// 使用相同的种子重新生成位移序列，参见 [`StringCipher::encrypt_fibonacci`]
+    pub fn decrypt_fibonacci(&self, text: &str, a: usize, b: usize) -> Result<String, CipherError> {
+        Ok(self.process(text, self.fibonacci_shifts(a, b), |c, k, n| (c + n - k) % n))
+    }
+    
+    /// 斐波那契位移函数：按密钥位置依次调用，每次返回下一个位移
+    fn fibonacci_shifts(&self, a: usize, b: usize) -> impl FnMut(usize) -> usize {
+        let n = self.modulus;
+        let (mut current, mut next) = (a % n, b % n);
+        move |_| {
+            let shift = current;
+            (current, next) = (next, (current + next) % n);
+            shift
+        }
+    }
+    
+    /// 判断密文是否由这个密码器和密钥加密明文得到
+    /// 
+    /// 加密 `plaintext` 后与 `ciphertext`（规范化后）做常数时间比较，
+    /// 比较耗时不泄露第一个不同字符的位置，适合测试工具和审计场景
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// assert!(cipher.is_ciphertext_of("HELLO", "RIJVS", "KEY").unwrap());
+    /// assert!(!cipher.is_ciphertext_of("HELLO", "RIJVT", "KEY").unwrap());
+    /// ```
+    pub fn is_ciphertext_of(&self, plaintext: &str, ciphertext: &str, key: &str) -> Result<bool, CipherError> {
+        let expected = self.encrypt(plaintext, key)?;
+        Ok(constant_time_eq(expected.as_bytes(), normalize(ciphertext).as_bytes()))
+    }
+    
+    /// 使用从读取器中读出的字节作为流式密钥加密
+    /// 
+    /// 每个字符集字符消耗一个密钥字节，位移为 `byte % modulus`（同 [`StringCipher::encrypt_bytes_key`]），
+    /// 但密钥**不循环使用**，类似书本密码的长密钥。只读取所需数量的字节；
+    /// 密钥在消息结束前耗尽时返回 [`CipherError::KeyExhausted`]，读取失败时返回 [`CipherError::Io`]
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// let key: &[u8] = &[1, 2, 3, 4, 5, 6];
+    /// assert_eq!(cipher.encrypt_with_keyfile("AAA AA", key).unwrap(), "BCD EF");
+    /// ```
+    pub fn encrypt_with_keyfile<R: Read>(&self, text: &str, key_reader: R) -> Result<String, CipherError> {
+        let key = self.read_running_key(text, key_reader)?;
+        Ok(self.process(text, |i| key[i] as usize % self.modulus, |m, k, n| (m + k) % n))
+    }
+    
+    /// 使用从读取器中读出的字节作为流式密钥解密
+    /// 
+    /// 参见 [`StringCipher::encrypt_with_keyfile`]
+    pub fn decrypt_with_keyfile<R: Read>(&self, text: &str, key_reader: R) -> Result<String, CipherError> {
+        let key = self.read_running_key(text, key_reader)?;
+        Ok(self.process(text, |i| key[i] as usize % self.modulus, |c, k, n| (c + n - k) % n))
+    }
+    
+    /// 读取与文本中字符集字符数量相同的密钥字节
+    fn read_running_key<R: Read>(&self, text: &str, key_reader: R) -> Result<Vec<u8>, CipherError> {
+        let needed = self.to_elements(text).len();
+        let mut key = Vec::with_capacity(needed);
+        key_reader
+            .take(needed as u64)
+            .read_to_end(&mut key)
+            .map_err(|e| CipherError::Io(e.to_string()))?;
+        
+        if key.len() < needed {
+            return Err(CipherError::KeyExhausted { needed, available: key.len() });
+        }
+        Ok(key)
+    }
+    
+    /// 获取只读视图
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::{CipherElement, StringCipher};
+    /// 
+    /// let cipher = StringCipher::new("ABC").unwrap();
+    /// let view = cipher.view();
+    /// assert_eq!(view.modulus(), 3);
+    /// assert_eq!(view.charset()[1].value(), 'B');
+    /// ```
+    pub fn view(&self) -> CipherView<'_> {
+        CipherView { charset: &self.charset, modulus: self.modulus }
+    }
+    
+    /// 加密并按密钥位置为密文着色（教学用）
+    /// 
+    /// 启用 `color` feature 时，每个密文字符按所用的密钥位置染上 ANSI 前景色，
+    /// 密钥重复的周期因此一目了然；原样保留的字符以暗色显示。
+    /// 未启用该 feature 时返回与 [`StringCipher::encrypt`] 相同的普通密文
+    /// 
+    /// # 示例
+    /// ```
+    /// use vigenere_demo::StringCipher;
+    /// 
+    /// let cipher = StringCipher::uppercase_alpha();
+    /// let colored = cipher.encrypt_colored("HI!", "KEY").unwrap();
+    /// # #[cfg(feature = "color")]
+    /// assert_eq!(colored, "\u{1b}[31mR\u{1b}[0m\u{1b}[32mM\u{1b}[0m\u{1b}[2m!\u{1b}[0m");
+    /// # #[cfg(not(feature = "color"))]
+    /// assert_eq!(colored, "RM!");
+    /// ```
+    pub fn encrypt_colored(&self, text: &str, key: &str) -> Result<String, CipherError> {
+        let key_elements = self.parse_key(key)?;
+        
+        #[cfg(feature = "color")]
+        {
+            use std::fmt::Write;
+            
+            /// 按密钥位置轮换的前景色：红、绿、黄、蓝、品红、青
+            const PALETTE: [u8; 6] = [31, 32, 33, 34, 35, 36];
+            
+            let shift = |i: usize| key_elements[i % key_elements.len()].index();
+            let text = normalize(text);
+            let mut result = String::with_capacity(text.len() * 10);
+            let mut key_index = 0;
+            
+            // 写入 String 不会失败，忽略 `write!` 的结果
+            for ch in text.chars() {
+                let position = key_index % key_elements.len();
+                match self.shift_char(ch, &mut key_index, shift, &|m, k, n| (m + k) % n) {
+                    Some(elem) => {
+                        let _ = write!(result, "\x1b[{}m{}\x1b[0m", PALETTE[position % PALETTE.len()], elem.value());
+                    }
+                    None => {
+                        let _ = write!(result, "\x1b[2m{}\x1b[0m", ch);
+                    }
+                }
+            }
+            
+            Ok(result)
+        }
+        
+        #[cfg(not(feature = "color"))]
+        Ok(self.encrypt_elements(text, &key_elements))
+    }
+    
+    /// 获取字符集信息
+    pub fn charset_info(&self) -> String {
+        let chars: String = self.charset.iter().map(|e| e.value()).collect();
+        format!("字符集大小: {}, 字符: \"{}\"", self.modulus, chars)
+    }
+}
+
+/// 返回字符的另一种大小写形式（仅限一对一映射），没有时返回 `None`
+fn swap_case(c: char) -> Option<char> {
+    let swapped = if c.is_lowercase() {
+        single_char(c.to_uppercase())
+    } else if c.is_uppercase() {
+        single_char(c.to_lowercase())
+    } else {
+        None
+    };
+    
+    swapped.filter(|&s| s != c)
+}
+
+/// 将 `c` 转换为与 `reference` 相同的大小写（无法一对一转换时原样返回）
+fn to_case_of(c: char, reference: char) -> char {
+    let converted = if reference.is_lowercase() {
+        single_char(c.to_lowercase())
+    } else {
+        single_char(c.to_uppercase())
+    };
+    
+    converted.unwrap_or(c)
+}
+
+/// 迭代器恰好产生一个字符时返回该字符
+fn single_char(mut chars: impl Iterator<Item = char>) -> Option<char> {
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+/// 往返密钥的位移函数：位置按周期 `2 * (len - 1)` 的三角波取密钥字符
+fn bouncing_shift(key: &[CharElement]) -> impl Fn(usize) -> usize + '_ {
+    let period = (2 * (key.len() - 1)).max(1);
+    move |i| {
+        let phase = i % period;
+        let position = if phase < key.len() { phase } else { period - phase };
+        key[position].index()
+    }
+}
+
+/// 加盐密钥的位移函数：先依次使用盐，再循环使用密钥
+fn salted_shift<'k>(salt: &'k [CharElement], key: &'k [CharElement]) -> impl Fn(usize) -> usize + 'k {
+    move |i| match salt.get(i) {
+        Some(elem) => elem.index(),
+        None => key[(i - salt.len()) % key.len()].index(),
+    }
+}
+
+/// 密钥的最小周期：最小的 `p` 使密钥等于前 `p` 个字符的重复
+/// 
+/// 例如 `"ABAB"` 的最小周期为 2，`"AAAA"` 为 1，`"ABC"` 为 3
+fn key_period(key: &[CharElement]) -> usize {
+    (1..=key.len())
+        .find(|&p| key.len().is_multiple_of(p) && key.iter().enumerate().all(|(i, e)| e.index() == key[i % p].index()))
+        .unwrap_or(key.len())
+}
+
+/// 常数时间比较两个字节串
+/// 
+/// 长度不同时直接返回 `false`（只泄露长度）；长度相同时总是比较全部字节，
+/// 耗时与第一个不同字节的位置无关
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    
+    let diff = a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    std::hint::black_box(diff) == 0
+}
+
+/// 计数器模式的混合函数（SplitMix64 终结函数）
+fn mix_counter(nonce: u64, i: usize) -> u64 {
+    let mut z = nonce ^ (i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// 将文本规范化为 NFC（需启用 `unicode-normalization` feature）
+/// 
+/// 保证 `é` 无论是单个码点还是 `e` + 组合重音符都映射到同一字符集元素
+#[cfg(feature = "unicode-normalization")]
+fn normalize(s: &str) -> Cow<'_, str> {
+    use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+    
+    if is_nfc_quick(s.chars()) == IsNormalized::Yes {
+        Cow::Borrowed(s)
+    } else {
+        Cow::Owned(s.nfc().collect())
+    }
+}
+
+/// 未启用 `unicode-normalization` feature 时原样返回
+#[cfg(not(feature = "unicode-normalization"))]
+fn normalize(s: &str) -> Cow<'_, str> {
+    Cow::Borrowed(s)
+}
+
+/// 按范围生成字符集时允许的最大字符数
+const MAX_GENERATED_CHARSET: usize = 65536;
+
+/// 由连续的 Unicode 范围 `start..=end` 创建密码器
+/// 
+/// 适合 CJK、希腊字母等大字符集，无需手动列出所有字符。范围内的代理码点会被自动跳过
+/// 
+/// # 错误
+/// - `start > end` 时返回 [`CipherError::InvalidRange`]
+/// - 字符数超过 65536 时返回 [`CipherError::CharsetTooLarge`]
+/// 
+/// # 示例
+/// ```
+/// use vigenere_demo::unicode_range_cipher;
+/// 
+/// let cipher = unicode_range_cipher('α', 'ω').unwrap();
+/// assert_eq!(cipher.modulus(), 25);
+/// ```
+pub fn unicode_range_cipher(start: char, end: char) -> Result<StringCipher, CipherError> {
+    if start > end {
+        return Err(CipherError::InvalidRange { start, end });
+    }
+    
+    // 直接按码点计算字符数，范围跨过代理区 U+D800..=U+DFFF 时扣除这 2048 个码点
+    let (start_code, end_code) = (start as usize, end as usize);
+    let surrogates = if start_code < 0xD800 && end_code > 0xDFFF { 0x800 } else { 0 };
+    let size = end_code - start_code + 1 - surrogates;
+    if size > MAX_GENERATED_CHARSET {
+        return Err(CipherError::CharsetTooLarge { size, max: MAX_GENERATED_CHARSET });
+    }
+    
+    StringCipher::from_chars(start..=end)
+}
+
+/// 由码点范围内满足条件的字符创建密码器
+/// 
+/// 按码点顺序收集 `range` 内所有满足 `predicate` 的 Unicode 标量值（代理码点和超出
+/// `char` 范围的值自动跳过），便于构建西里尔字母等不连续的大字母表
+/// 
+/// # 错误
+/// - 没有字符满足条件时返回 [`CipherError::EmptyCharset`]
+/// - 字符数超过 65536 时返回 [`CipherError::CharsetTooLarge`]
+/// 
+/// # 示例
+/// ```
+/// use vigenere_demo::charset_from_category;
+/// 
+/// let cipher = charset_from_category(|c| c.is_ascii_digit(), 0..=0x7F).unwrap();
+/// assert_eq!(cipher.modulus(), 10);
+/// ```
+pub fn charset_from_category(
+    predicate: impl Fn(char) -> bool,
+    range: RangeInclusive<u32>,
+) -> Result<StringCipher, CipherError> {
+    let chars: Vec<char> = range.filter_map(char::from_u32).filter(|&c| predicate(c)).collect();
+    if chars.len() > MAX_GENERATED_CHARSET {
+        return Err(CipherError::CharsetTooLarge { size: chars.len(), max: MAX_GENERATED_CHARSET });
+    }
+    
+    StringCipher::from_chars(chars)
+}
+
+/// 创建 `base` 进制数字字符集的密码器
+/// 
+/// 字符集为 `0-9A-Z` 的前 `base` 个字符（大写字母表示 10 以上的数字），
+/// 可用于加密十六进制、三十六进制等数字串。`base` 不在 `2..=36` 范围内时返回
+/// [`CipherError::InvalidBase`]
+/// 
+/// # 示例
+/// ```
+/// use vigenere_demo::base_n_cipher;
+/// 
+/// let hex = base_n_cipher(16).unwrap();
+/// assert_eq!(hex.encrypt("00FF", "1").unwrap(), "1100");
+/// ```
+pub fn base_n_cipher(base: usize) -> Result<StringCipher, CipherError> {
+    const DIGITS: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    
+    if !(2..=DIGITS.len()).contains(&base) {
+        return Err(CipherError::InvalidBase(base));
+    }
+    
+    StringCipher::new(&DIGITS[..base])
+}
+
+/// 每 `width` 个字符插入一个换行，便于在终端显示长文本
+/// 
+/// 按字符（而非字节）计数，末尾不追加换行；`width` 为 0 时原样返回
+/// 
+/// # 示例
+/// ```
+/// use vigenere_demo::wrap_output;
+/// 
+/// assert_eq!(wrap_output("ABCDEFG", 3), "ABC\nDEF\nG");
+/// ```
+pub fn wrap_output(s: &str, width: usize) -> String {
+    if width == 0 {
+        return s.to_string();
+    }
+    
+    let mut result = String::with_capacity(s.len() + s.len() / width);
+    for (i, ch) in s.chars().enumerate() {
+        if i > 0 && i % width == 0 {
+            result.push('\n');
+        }
+        result.push(ch);
+    }
+    result
+}
+
+/// 加密结果的分段，由 [`StringCipher::encrypt_segments`] 产生
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    /// 字符集中的字符：原字符及其密文
+    Encrypted { original: char, output: char },
+    /// 不在字符集中、原样保留的字符
+    Preserved(char),
+}
+
+/// 两个字符集的差异，由 [`StringCipher::diff`] 产生
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharsetDiff {
+    /// 只在当前字符集中的字符（按当前字符集顺序）
+    pub only_in_self: Vec<char>,
+    /// 只在另一个字符集中的字符（按另一个字符集顺序）
+    pub only_in_other: Vec<char>,
+    /// 是否存在两边都有、但索引不同的字符
+    pub reordered: bool,
+}
+
+/// 字符在字符集中的状态，由 [`StringCipher::explain_char`] 产生
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharStatus {
+    /// 在字符集中，附带其索引
+    InCharset(usize),
+    /// 不在字符集中，但其另一种大小写形式在（附带该字符集字符）
+    CaseMismatch(char),
+    /// 不在字符集中，加解密时原样保留
+    NotInCharset,
+}
+
+/// 加密时发现的密钥安全隐患，由 [`StringCipher::encrypt_checked`] 产生
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Warning {
+    /// 密钥短于 [`StringCipher::MIN_KEY_LEN`] 个字符，容易被穷举
+    ShortKey,
+    /// 消息中的字符集字符数是密钥长度的整数倍（至少两个周期），各列对齐便于统计分析
+    KeyLenDividesTextLen,
+    /// 密钥由更短的片段重复组成（如 `"ABAB"`），实际周期比密钥长度短
+    RepetitiveKey,
+}
+
+/// 密码器的只读视图，由 [`StringCipher::view`] 创建
+/// 
+/// 只借用字符集，可在不传递整个密码器的情况下向界面等展示字符集信息
+#[derive(Debug, Clone, Copy)]
+pub struct CipherView<'a> {
+    charset: &'a [CharElement],
+    modulus: usize,
+}
+
+impl<'a> CipherView<'a> {
+    /// 获取字符集元素
+    pub fn charset(&self) -> &'a [CharElement] {
+        self.charset
+    }
+    
+    /// 获取字符集大小（模数）
+    pub fn modulus(&self) -> usize {
+        self.modulus
+    }
+}
+
+/// 绑定了已校验密钥的密码器
+/// 
+/// 由 [`StringCipher::with_key`] 创建，避免批量处理时每次重新解析密钥，
+/// 也避免把密钥误用在不匹配的字符集上
+#[derive(Debug, Clone)]
+pub struct KeyedCipher<'a> {
+    cipher: &'a StringCipher,
+    key: Vec<CharElement>,
+}
+
+impl KeyedCipher<'_> {
+    /// 加密字符串，行为与 [`StringCipher::encrypt`] 一致
+    pub fn encrypt(&self, plaintext: &str) -> Result<String, CipherError> {
+        Ok(self.cipher.encrypt_elements(plaintext, &self.key))
+    }
+    
+    /// 解密字符串，行为与 [`StringCipher::decrypt`] 一致
+    pub fn decrypt(&self, ciphertext: &str) -> Result<String, CipherError> {
+        Ok(self.cipher.decrypt_elements(ciphertext, &self.key))
+    }
+}
+
+/// 预先校验并解析好的密钥
+/// 
+/// 由 [`StringCipher::prepare_key`] 创建。与 [`KeyedCipher`] 不同，它不借用密码器，
+/// 可以单独保存，适合很长的密钥（如滚动密钥）在多次加密间复用，避免每次重新解析。
+/// 密钥以索引形式保存，并记录创建它的字符集指纹，只能与字符集相同的密码器搭配使用
+#[derive(Debug, Clone)]
+pub struct PreparedKey {
+    elements: Vec<CharElement>,
+    fingerprint: u32,
+}
+
+impl PreparedKey {
+    /// 密钥长度（字符数）
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+    
+    /// 密钥是否为空（总是 `false`，空密钥无法通过校验）
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+}
+
+// ==================== 单元测试 ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    // === StringCipher 测试 ===
+    
+    #[test]
+    fn test_string_basic_encryption() {
+        let cipher = StringCipher::uppercase_alpha();
+        let encrypted = cipher.encrypt("HELLO", "KEY").unwrap();
+        assert_eq!(encrypted, "RIJVS");
+    }
+
+    #[test]
+    fn test_string_basic_decryption() {
+        let cipher = StringCipher::uppercase_alpha();
+        let decrypted = cipher.decrypt("RIJVS", "KEY").unwrap();
+        assert_eq!(decrypted, "HELLO");
+    }
+
+    #[test]
+    fn test_string_round_trip() {
+        let cipher = StringCipher::alphanumeric();
+        let original = "Hello123World";
+        let encrypted = cipher.encrypt(original, "SecretKey").unwrap();
+        let decrypted = cipher.decrypt(&encrypted, "SecretKey").unwrap();
+        assert_eq!(original, decrypted);
+    }
+
+    #[test]
+    fn test_string_custom_charset() {
+        let cipher = StringCipher::new("0123456789").unwrap();
+        let encrypted = cipher.encrypt("123", "456").unwrap();
+        assert_eq!(encrypted, "579");
+    }
+
+    #[test]
+    fn test_string_preserve_unknown_chars() {
+        let cipher = StringCipher::uppercase_alpha();
+        let encrypted = cipher.encrypt("HELLO, WORLD!", "KEY").unwrap();
+        // 逗号、空格、感叹号保持不变
+        assert_eq!(encrypted, "RIJVS, UYVJN!");
+    }
+
+    #[test]
+    fn test_string_empty_key_error() {
+        let cipher = StringCipher::uppercase_alpha();
+        assert!(cipher.encrypt("HELLO", "").is_err());
+    }
+
+    #[test]
+    fn test_string_invalid_key_char() {
+        let cipher = StringCipher::uppercase_alpha();
+        assert!(cipher.encrypt("HELLO", "key").is_err()); // 小写字母不在大写字符集中
+    }
+
+    #[test]
+    fn test_string_duplicate_charset_error() {
+        let result = StringCipher::new("ABBA");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_inverse_for_key() {
+        let cipher = StringCipher::printable_ascii();
+        let ciphertext = "Attack at dawn! ~{|}";
+        let inverse = cipher.inverse_for_key("Secret Key").unwrap();
+        assert_eq!(
+            cipher.encrypt(ciphertext, &inverse).unwrap(),
+            cipher.decrypt(ciphertext, "Secret Key").unwrap()
+        );
+        assert_eq!(
+            StringCipher::uppercase_alpha().inverse_for_key("AZ"),
+            Ok("AB".to_string())
+        );
+        assert!(cipher.inverse_for_key("").is_err());
+    }
+    
+    #[test]
+    fn test_keyed_cipher_invalid_key() {
+        let cipher = StringCipher::uppercase_alpha();
+        assert_eq!(cipher.with_key("").unwrap_err(), CipherError::EmptyKey);
+        assert_eq!(cipher.with_key("key").unwrap_err(), CipherError::CharNotInCharset('k'));
     }
-}
 
-// ==================== 单元测试 ====================
+    #[test]
+    fn test_keyed_cipher_matches_per_call() {
+        let cipher = StringCipher::alphanumeric();
+        let keyed = cipher.with_key("SecretKey").unwrap();
+        for text in ["Hello123World", "Attack at dawn!", ""] {
+            let encrypted = keyed.encrypt(text).unwrap();
+            assert_eq!(encrypted, cipher.encrypt(text, "SecretKey").unwrap());
+            assert_eq!(keyed.decrypt(&encrypted).unwrap(), text);
+        }
+    }
+    
+    #[cfg(feature = "unicode-normalization")]
+    #[test]
+    fn test_nfc_normalization() {
+        let composed = "\u{e9}"; // é
+        let decomposed = "e\u{301}"; // e + 组合重音符
+        
+        let cipher = StringCipher::new(&format!("abcd{}", decomposed)).unwrap();
+        assert_eq!(cipher.modulus, 5);
+        
+        let from_composed = cipher.encrypt(composed, "b").unwrap();
+        let from_decomposed = cipher.encrypt(decomposed, "b").unwrap();
+        assert_eq!(from_composed, "a");
+        assert_eq!(from_composed, from_decomposed);
+        assert_eq!(cipher.with_key(decomposed).unwrap().encrypt("a").unwrap(), composed);
+    }
+    
+    #[test]
+    fn test_keystream_elements() {
+        let cipher = StringCipher::uppercase_alpha();
+        let key = "KEY";
+        let stream = cipher.keystream_elements(key, 8).unwrap();
+        assert_eq!(stream.len(), 8);
+        assert_eq!(&stream[..key.len()], cipher.parse_string(key).unwrap().as_slice());
+        assert_eq!(stream[3], stream[0]);
+        assert!(cipher.keystream_elements(key, 0).unwrap().is_empty());
+        assert!(cipher.keystream_elements("", 3).is_err());
+    }
+    
+    #[test]
+    fn test_counter_round_trip() {
+        let cipher = StringCipher::printable_ascii();
+        let text = "Attack at dawn, retreat at dusk!";
+        let encrypted = cipher.encrypt_counter(text, "Secret", 7).unwrap();
+        assert_ne!(encrypted, text);
+        assert_eq!(cipher.decrypt_counter(&encrypted, "Secret", 7).unwrap(), text);
+        assert_ne!(cipher.decrypt_counter(&encrypted, "Secret", 8).unwrap(), text);
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_counter_nonce_changes_ciphertext() {
+        let cipher = StringCipher::uppercase_alpha();
+        let text = "ATTACKATDAWNATTACKATDAWN";
+        let a = cipher.encrypt_counter(text, "KEY", 1).unwrap();
+        let b = cipher.encrypt_counter(text, "KEY", 2).unwrap();
+        assert_ne!(a, b);
+        assert_eq!(a, cipher.encrypt_counter(text, "KEY", 1).unwrap());
+    }
+    
+    #[test]
+    fn test_presets() {
+        let expected = [
+            ("uppercase_alpha", "大写英文字母 (A-Z)", 26),
+            ("lowercase_alpha", "小写英文字母 (a-z)", 26),
+            ("mixed_alpha", "大小写英文字母", 52),
+            ("alphanumeric", "字母+数字", 62),
+            ("printable_ascii", "可打印ASCII字符", 95),
+        ];
+        let presets = StringCipher::presets();
+        assert_eq!(presets.len(), expected.len());
+        for ((name, label, cipher), (expected_name, expected_label, expected_modulus)) in presets.iter().zip(expected) {
+            assert_eq!(*name, expected_name);
+            assert_eq!(*label, expected_label);
+            assert_eq!(cipher.modulus, expected_modulus);
+        }
+    }
+    
+    #[test]
+    fn test_self_test() {
+        assert_eq!(StringCipher::self_test(), Ok(()));
+    }
+    
+    #[test]
+    fn test_from_chars() {
+        let chars = vec!['X', 'Y', 'Z'];
+        let cipher = StringCipher::from_chars(chars).unwrap();
+        assert_eq!(cipher.encrypt("XYZ", "Y").unwrap(), "YZX");
+        
+        let duplicated = vec!['A', 'B', 'C', 'B'];
+        assert_eq!(StringCipher::from_chars(duplicated).unwrap_err(), CipherError::DuplicateChar('B'));
+        assert_eq!(StringCipher::from_chars(Vec::new()).unwrap_err(), CipherError::EmptyCharset);
+    }
+    
+    #[test]
+    fn test_hex_round_trip() {
+        let cipher = StringCipher::printable_ascii();
+        let text = "Grüße, World!";
+        let hex = cipher.encrypt_hex(text, "Key").unwrap();
+        assert!(hex.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(cipher.decrypt_hex(&hex, "Key").unwrap(), text);
+        
+        assert_eq!(cipher.decrypt_hex("abc", "Key").unwrap_err(), CipherError::InvalidHex);
+        assert_eq!(cipher.decrypt_hex("ff", "Key").unwrap_err(), CipherError::InvalidHex);
+    }
+    
+    #[test]
+    fn test_encrypt_with_map() {
+        let cipher = StringCipher::uppercase_alpha();
+        let (encrypted, map) = cipher.encrypt_with_map("HELLO", "KEY").unwrap();
+        assert_eq!(encrypted, "RIJVS");
+        assert_eq!(map, [Some(0), Some(1), Some(2), Some(0), Some(1)]);
+        
+        let (encrypted, map) = cipher.encrypt_with_map("HE LLO!", "KEY").unwrap();
+        assert_eq!(encrypted, "RI JVS!");
+        assert_eq!(map, [Some(0), Some(1), None, Some(2), Some(0), Some(1), None]);
+        assert_eq!(map.len(), encrypted.chars().count());
+    }
+    
+    #[test]
+    fn test_progressive_round_trip() {
+        let cipher = StringCipher::printable_ascii();
+        let text = "The quick brown fox jumps over the lazy dog.";
+        let encrypted = cipher.encrypt_progressive(text, "Key", 3).unwrap();
+        assert_ne!(encrypted, cipher.encrypt(text, "Key").unwrap());
+        assert_eq!(cipher.decrypt_progressive(&encrypted, "Key", 3).unwrap(), text);
+    }
 
-    // === StringCipher 测试 ===
+    #[test]
+    fn test_progressive_zero_step_is_vigenere() {
+        let cipher = StringCipher::uppercase_alpha();
+        let text = "ATTACK AT DAWN";
+        assert_eq!(
+            cipher.encrypt_progressive(text, "KEY", 0).unwrap(),
+            cipher.encrypt(text, "KEY").unwrap()
+        );
+    }
+    
+    #[test]
+    fn test_encrypt_columns() {
+        let cipher = StringCipher::alphanumeric();
+        let columns = ["Alice", "Bob 42", "", "Carol-Smith"];
+        let encrypted = cipher.encrypt_columns(&columns, "Secret").unwrap();
+        assert_eq!(encrypted.len(), columns.len());
+        for (column, encrypted) in columns.iter().zip(&encrypted) {
+            assert_eq!(*encrypted, cipher.encrypt(column, "Secret").unwrap());
+        }
+        assert!(cipher.encrypt_columns(&columns, "").is_err());
+    }
+    
+    #[test]
+    fn test_looks_random() {
+        let cipher = StringCipher::uppercase_alpha();
+        let english = "IT WAS THE BEST OF TIMES IT WAS THE WORST OF TIMES IT WAS THE AGE OF WISDOM \
+                       IT WAS THE AGE OF FOOLISHNESS IT WAS THE EPOCH OF BELIEF";
+        assert!(!cipher.looks_random(english));
+        
+        // 线性同余生成器产生的伪随机字母
+        let mut state: u32 = 12345;
+        let random: String = (0..500)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (b'A' + ((state >> 16) % 26) as u8) as char
+            })
+            .collect();
+        assert!(cipher.looks_random(&random));
+        
+        // 样本太短时不做判断
+        assert!(!cipher.looks_random("QXZJ"));
+    }
+    
+    #[test]
+    fn test_digits_u64_round_trip() {
+        let digits = u64_to_digits(12345);
+        let chars: String = digits.iter().map(|d| d.to_char()).collect();
+        assert_eq!(chars, "12345");
+        assert_eq!(digits_to_u64(&digits), Some(12345));
+        assert_eq!(u64_to_digits(0), [DigitElement::new(0).unwrap()]);
+        assert_eq!(digits_to_u64(&u64_to_digits(u64::MAX)), Some(u64::MAX));
+    }
+
+    #[test]
+    fn test_digits_to_u64_overflow() {
+        // u64::MAX = 18446744073709551615，再多一位必然溢出
+        let mut digits = u64_to_digits(u64::MAX);
+        digits.push(DigitElement::new(0).unwrap());
+        assert_eq!(digits_to_u64(&digits), None);
+        
+        // 同样位数但数值为 u64::MAX + 1
+        let mut bumped = u64_to_digits(u64::MAX);
+        *bumped.last_mut().unwrap() = DigitElement::new(6).unwrap();
+        assert_eq!(digits_to_u64(&bumped), None);
+        assert_eq!(digits_to_u64(&[]), None);
+    }
+    
+    #[test]
+    fn test_as_caesar_shifts() {
+        let cipher = StringCipher::uppercase_alpha();
+        assert_eq!(cipher.as_caesar_shifts("KEY").unwrap(), vec![10, 4, 24]);
+        assert!(cipher.as_caesar_shifts("").is_err());
+        
+        let shifts = StringCipher::lowercase_alpha().as_caesar_shifts("abz").unwrap();
+        assert_eq!(shifts, vec![0, 1, 25]);
+    }
+    
+    #[test]
+    fn test_caesar_brute_force() {
+        let cipher = StringCipher::uppercase_alpha();
+        let ciphertext = cipher.encrypt("MEET ME AT NOON", "H").unwrap();
+        let candidates = cipher.caesar_brute_force(&ciphertext);
+        assert_eq!(candidates.len(), cipher.modulus);
+        assert_eq!(candidates[0], (0, ciphertext.clone()));
+        assert!(candidates.contains(&(7, "MEET ME AT NOON".to_string())));
+    }
+    
+    #[test]
+    fn test_encrypt_strong() {
+        let cipher = StringCipher::uppercase_alpha();
+        assert_eq!(cipher.encrypt_strong("HELLO", "AAAA"), Err(CipherError::WeakKey));
+        assert_eq!(cipher.encrypt_strong("HELLO", "K"), Err(CipherError::WeakKey));
+        assert_eq!(cipher.encrypt_strong("HELLO", ""), Err(CipherError::EmptyKey));
+        assert_eq!(cipher.encrypt_strong("HELLO", "KEY"), cipher.encrypt("HELLO", "KEY"));
+        assert!(cipher.encrypt_strong("HELLO", "KK K").is_err()); // 空格不在字符集中
+    }
+    
+    #[test]
+    fn test_encrypt_segments() {
+        let cipher = StringCipher::uppercase_alpha();
+        let segments = cipher.encrypt_segments("HI!", "KEY").unwrap();
+        assert_eq!(
+            segments,
+            vec![
+                Segment::Encrypted { original: 'H', output: 'R' },
+                Segment::Encrypted { original: 'I', output: 'M' },
+                Segment::Preserved('!'),
+            ]
+        );
+        assert!(cipher.encrypt_segments("", "KEY").unwrap().is_empty());
+    }
+    
+    #[test]
+    fn test_case_folded_uppercase_charset() {
+        let cipher = StringCipher::uppercase_alpha();
+        let encrypted = cipher.encrypt_case_folded("Hello, World!", "KEY").unwrap();
+        assert_eq!(encrypted, "Rijvs, Uyvjn!");
+        assert_eq!(
+            encrypted.to_uppercase(),
+            cipher.encrypt("HELLO, WORLD!", "KEY").unwrap()
+        );
+        assert_eq!(cipher.decrypt_case_folded(&encrypted, "KEY").unwrap(), "Hello, World!");
+    }
+
+    #[test]
+    fn test_case_folded_mixed_charset_untouched() {
+        let cipher = StringCipher::mixed_alpha();
+        let text = "Hello, World!";
+        assert_eq!(
+            cipher.encrypt_case_folded(text, "Key").unwrap(),
+            cipher.encrypt(text, "Key").unwrap()
+        );
+        
+        // 折叠方向取决于字符集包含哪种形式
+        let partial = StringCipher::new("ABCdef").unwrap();
+        assert_eq!(partial.encrypt_case_folded("aD", "B").unwrap(), "bE");
+        // 输出字符没有大小写时原样输出
+        let digits_and_upper = StringCipher::new("AB01").unwrap();
+        assert_eq!(digits_and_upper.encrypt_case_folded("ab", "1").unwrap(), "1a");
+    }
+    
+    #[test]
+    fn test_safe_length_for_key() {
+        let cipher = StringCipher::uppercase_alpha();
+        assert_eq!(cipher.safe_length_for_key("KEY"), 3);
+        assert_eq!(cipher.safe_length_for_key(""), 0);
+        assert_eq!(cipher.safe_length_for_key("key"), 0);
+    }
+    
+    #[test]
+    fn test_bit_conversion() {
+        let bits = bytes_to_bits(&[0b1010_0001]);
+        let values: Vec<bool> = bits.iter().map(|b| b.value()).collect();
+        assert_eq!(values, [true, false, true, false, false, false, false, true]);
+        assert_eq!(bits_to_bytes(&bits), [0b1010_0001]);
+        assert_eq!(bits_to_bytes(&bits[..3]), [0b1010_0000]);
+    }
+
+    #[test]
+    fn test_bit_cipher_is_xor() {
+        let cipher = bit_cipher();
+        assert_eq!(cipher.modulus(), 2);
+        
+        let data = b"Attack at dawn";
+        let key = b"KEY";
+        let key_bits = bytes_to_bits(key);
+        let encrypted = cipher.encrypt(&bytes_to_bits(data), NonEmptySliceRef::new(&key_bits).unwrap());
+        
+        let xored: Vec<u8> = data.iter().zip(key.iter().cycle()).map(|(d, k)| d ^ k).collect();
+        assert_eq!(bits_to_bytes(&encrypted), xored);
+        
+        let decrypted = cipher.decrypt(&encrypted, NonEmptySliceRef::new(&key_bits).unwrap());
+        assert_eq!(bits_to_bytes(&decrypted), data);
+    }
+    
+    #[cfg(feature = "cache")]
+    #[test]
+    fn test_key_cache_correctness() {
+        let plain = StringCipher::alphanumeric();
+        let cached = StringCipher::alphanumeric().with_key_cache(2);
+        let keys = ["Alpha", "Bravo", "Charlie", "Alpha", "Bravo", "Alpha"];
+        
+        for key in keys {
+            let encrypted = cached.encrypt("Attack at 0600", key).unwrap();
+            assert_eq!(encrypted, plain.encrypt("Attack at 0600", key).unwrap());
+            assert_eq!(cached.decrypt(&encrypted, key).unwrap(), "Attack at 0600");
+        }
+        assert_eq!(cached.key_cache.as_ref().unwrap().lock().unwrap().len(), 2);
+        
+        // 非法密钥不进入缓存
+        assert!(cached.encrypt("HELLO", "bad key!").is_err());
+        assert!(cached.encrypt("HELLO", "").is_err());
+        assert!(!cached.key_cache.as_ref().unwrap().lock().unwrap().contains("bad key!"));
+        
+        assert!(StringCipher::alphanumeric().with_key_cache(0).key_cache.is_none());
+    }
+    
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_vectors_deterministic() {
+        let cipher = StringCipher::printable_ascii();
+        let vectors = cipher.test_vectors(20, 2024);
+        assert_eq!(vectors.len(), 20);
+        assert_eq!(vectors, cipher.test_vectors(20, 2024));
+        assert_ne!(vectors, cipher.test_vectors(20, 2025));
+        
+        for (plaintext, key, ciphertext) in &vectors {
+            assert!((1..=32).contains(&plaintext.chars().count()));
+            assert!((1..=8).contains(&key.chars().count()));
+            assert_eq!(cipher.encrypt(plaintext, key).unwrap(), *ciphertext);
+        }
+    }
+    
+    #[test]
+    fn test_new_warn_case() {
+        let (cipher, conflicts) = StringCipher::new_warn_case("ABCa").unwrap();
+        assert_eq!(conflicts, vec!['A', 'a']);
+        assert_eq!(cipher.modulus, 4);
+        
+        let (_, conflicts) = StringCipher::new_warn_case("ABC123").unwrap();
+        assert!(conflicts.is_empty());
+        
+        let (_, conflicts) = StringCipher::new_warn_case("xYyX").unwrap();
+        assert_eq!(conflicts, vec!['x', 'Y', 'y', 'X']);
+        
+        assert!(StringCipher::new_warn_case("AA").is_err());
+    }
+    
+    #[test]
+    fn test_impl_cipher_element_dna() {
+        #[derive(Debug, Clone, PartialEq)]
+        enum Base {
+            A,
+            C,
+            G,
+            T,
+        }
+        
+        impl_cipher_element!(Base { A, C, G, T });
+        
+        use Base::*;
+        assert_eq!([A.index(), C.index(), G.index(), T.index()], [0, 1, 2, 3]);
+        
+        let cipher = VigenereCipher::new(NonEmptyVec::try_from_vec(vec![A, C, G, T]).unwrap());
+        let sequence = vec![G, A, T, T, A, C, A];
+        let key = [C, G];
+        let key = NonEmptySliceRef::new(key.as_slice()).unwrap();
+        
+        // G+C=T, A+G=G, T+C=A, T+G=C, A+C=C, C+G=T, A+C=C
+        let encrypted = cipher.encrypt(&sequence, key);
+        assert_eq!(encrypted, vec![T, G, A, C, C, T, C]);
+        assert_eq!(cipher.decrypt(&encrypted, key), sequence);
+    }
+    
+    #[test]
+    fn test_charset_from_category_cyrillic() {
+        // 基本西里尔字母 А-я 共 64 个，0x0400 区块中的非字母符号（如 ҂）被过滤
+        let cipher = charset_from_category(char::is_alphabetic, 0x0410..=0x044F).unwrap();
+        assert_eq!(cipher.modulus(), 64);
+        let block = charset_from_category(char::is_alphabetic, 0x0400..=0x04FF).unwrap();
+        assert_eq!(block.explain_char('҂'), CharStatus::NotInCharset);
+        
+        let encrypted = cipher.encrypt("ПРИВЕТ, МИР", "КЛЮЧ").unwrap();
+        assert_ne!(encrypted, "ПРИВЕТ, МИР");
+        assert_eq!(cipher.decrypt(&encrypted, "КЛЮЧ").unwrap(), "ПРИВЕТ, МИР");
+        
+        assert_eq!(
+            charset_from_category(|_| false, 0x0400..=0x04FF).unwrap_err(),
+            CipherError::EmptyCharset
+        );
+        assert!(matches!(
+            charset_from_category(char::is_alphabetic, 0..=0x10FFFF),
+            Err(CipherError::CharsetTooLarge { max: 65536, .. })
+        ));
+    }
+    
+    #[test]
+    fn test_unicode_range_cipher_greek() {
+        let cipher = unicode_range_cipher('α', 'ω').unwrap();
+        assert_eq!(cipher.modulus, 25); // 含词尾 ς
+        
+        let encrypted = cipher.encrypt("λόγος", "β").unwrap();
+        assert_eq!(encrypted, "μόδπσ"); // 带重音的 ό 不在范围内，保持不变
+        assert_eq!(cipher.decrypt(&encrypted, "β").unwrap(), "λόγος");
+        assert_eq!(cipher.encrypt("ω", "β").unwrap(), "α");
+    }
+
+    #[test]
+    fn test_unicode_range_cipher_errors() {
+        assert_eq!(
+            unicode_range_cipher('z', 'a').unwrap_err(),
+            CipherError::InvalidRange { start: 'z', end: 'a' }
+        );
+        assert!(matches!(
+            unicode_range_cipher('\u{0}', '\u{10FFFF}'),
+            Err(CipherError::CharsetTooLarge { .. })
+        ));
+        assert_eq!(unicode_range_cipher('A', 'A').unwrap().modulus, 1);
+        // 跨过代理区时扣除 2048 个代理码点
+        assert_eq!(unicode_range_cipher('\u{D000}', '\u{E7FF}').unwrap().modulus, 0x1800 - 0x800);
+    }
+    
+    #[test]
+    fn test_decrypt_with_known_keystream() {
+        let cipher = StringCipher::uppercase_alpha();
+        let plaintext = "ATTACK AT DAWN!";
+        let key = "LEMON";
+        let ciphertext = cipher.encrypt(plaintext, key).unwrap();
+        
+        // 完整密钥流（与明文等长）可以还原明文
+        let keystream: Vec<usize> = cipher
+            .keystream_elements(key, cipher.to_elements(plaintext).len())
+            .unwrap()
+            .iter()
+            .map(|e| e.index())
+            .collect();
+        assert_eq!(cipher.decrypt_with_known_keystream(&ciphertext, &keystream).unwrap(), plaintext);
+        
+        // 较短的位移序列循环使用，位移对模数取模
+        assert_eq!(cipher.decrypt_with_known_keystream(&ciphertext, &[11, 4, 12, 14, 13]).unwrap(), plaintext);
+        assert_eq!(cipher.decrypt_with_known_keystream(&ciphertext, &[37, 30, 38, 40, 39]).unwrap(), plaintext);
+        assert_eq!(cipher.decrypt_with_known_keystream(&ciphertext, &[]), Err(CipherError::EmptyKey));
+    }
+    
+    #[test]
+    fn test_brute_force_keys() {
+        let cipher = StringCipher::new("ABCD").unwrap();
+        let ciphertext = cipher.encrypt("AAAAAB-AB", "DB").unwrap();
+        let score = |p: &str| p.matches('A').count() as f64;
+        
+        let all = cipher.brute_force_keys(&ciphertext, 2, score, 100).unwrap();
+        assert_eq!(all.len(), 16);
+        assert_eq!(all[0], ("DB".to_string(), "AAAAAB-AB".to_string(), 6.0));
+        assert!(all.windows(2).all(|w| w[0].2 >= w[1].2));
+        
+        let top = cipher.brute_force_keys(&ciphertext, 2, score, 3).unwrap();
+        assert_eq!(top, all[..3]);
+        assert!(cipher.brute_force_keys(&ciphertext, 2, score, 0).unwrap().is_empty());
+        
+        // NaN 得分的密钥被丢弃，不会排在最前
+        let nan_for_d = |p: &str| if p.starts_with('D') { f64::NAN } else { score(p) };
+        let filtered = cipher.brute_force_keys(&ciphertext, 2, nan_for_d, 100).unwrap();
+        assert!(filtered.iter().all(|(_, plaintext, value)| !plaintext.starts_with('D') && !value.is_nan()));
+        assert_eq!(filtered.len(), 12);
+        
+        assert_eq!(cipher.brute_force_keys(&ciphertext, 0, score, 3), Err(CipherError::EmptyKey));
+        assert_eq!(
+            StringCipher::uppercase_alpha().brute_force_keys("ABC", 5, score, 3),
+            Err(CipherError::KeyspaceTooLarge { modulus: 26, key_len: 5, max: 1_000_000 })
+        );
+    }
+    
+    #[test]
+    fn test_checksum_detects_tampering() {
+        let cipher = StringCipher::uppercase_alpha();
+        let (ciphertext, checksum) = cipher.encrypt_with_checksum("ATTACK AT DAWN", "LEMON").unwrap();
+        assert_eq!(ciphertext, cipher.encrypt("ATTACK AT DAWN", "LEMON").unwrap());
+        assert_eq!(cipher.decrypt_verify(&ciphertext, "LEMON", checksum).unwrap(), "ATTACK AT DAWN");
+        
+        // 翻转一个密文字符
+        let mut tampered: Vec<char> = ciphertext.chars().collect();
+        tampered[0] = if tampered[0] == 'A' { 'B' } else { 'A' };
+        let tampered: String = tampered.into_iter().collect();
+        assert!(matches!(
+            cipher.decrypt_verify(&tampered, "LEMON", checksum),
+            Err(CipherError::ChecksumMismatch { expected, .. }) if expected == checksum
+        ));
+    }
+    
+    #[test]
+    fn test_iter_indexed() {
+        let cipher = StringCipher::uppercase_alpha();
+        let pairs: Vec<(usize, char)> = cipher.iter_indexed().collect();
+        assert_eq!(pairs.len(), 26);
+        for (i, (index, c)) in pairs.into_iter().enumerate() {
+            assert_eq!(index, i);
+            assert_eq!(c, (b'A' + i as u8) as char);
+        }
+    }
+    
+    #[test]
+    fn test_codepoint_key() {
+        let cipher = StringCipher::uppercase_alpha();
+        let encrypted = cipher.encrypt_codepoint_key("HELLO, WORLD!", "🔑k").unwrap();
+        // '🔑' = 128273 → 15, 'k' = 107 → 3
+        assert_eq!(encrypted, cipher.encrypt("HELLO, WORLD!", "PD").unwrap());
+        assert_eq!(encrypted, cipher.encrypt_codepoint_key("HELLO, WORLD!", "🔑k").unwrap());
+        assert_eq!(cipher.decrypt_codepoint_key(&encrypted, "🔑k").unwrap(), "HELLO, WORLD!");
+        assert_eq!(cipher.encrypt_codepoint_key("HELLO", ""), Err(CipherError::EmptyKey));
+    }
+    
+    #[test]
+    fn test_bouncing_key() {
+        let cipher = StringCipher::uppercase_alpha();
+        assert_eq!(cipher.encrypt_bouncing_key("AAAAAAA", "ABC").unwrap(), "ABCBABC");
+        assert_eq!(cipher.encrypt_bouncing_key("AAAA", "K").unwrap(), "KKKK");
+        
+        let text = "THE QUICK BROWN FOX JUMPS OVER THE LAZY DOG";
+        let encrypted = cipher.encrypt_bouncing_key(text, "LEMON").unwrap();
+        assert_ne!(encrypted, cipher.encrypt(text, "LEMON").unwrap());
+        assert_eq!(cipher.decrypt_bouncing_key(&encrypted, "LEMON").unwrap(), text);
+    }
+    
+    #[test]
+    fn test_autokey_round_trip() {
+        let cipher = StringCipher::uppercase_alpha();
+        let text = "MEET ME AT THE USUAL PLACE, 10 PM!";
+        let encrypted = cipher.encrypt_autokey(text, "KEY").unwrap();
+        // 前 3 个字符与普通维吉尼亚密码一致
+        assert_eq!(encrypted[..3], cipher.encrypt(text, "KEY").unwrap()[..3]);
+        assert_eq!(cipher.decrypt_autokey(&encrypted, "KEY").unwrap(), text);
+        assert_eq!(cipher.encrypt_autokey(text, ""), Err(CipherError::EmptyKey));
+    }
+    
+    #[test]
+    fn test_decrypt_autokey_verify() {
+        let cipher = StringCipher::uppercase_alpha();
+        let encrypted = cipher.encrypt_autokey("ATTACK AT DAWN", "QUEEN").unwrap();
+        let plausible = |p: &str| p.contains("DAWN");
+        
+        assert_eq!(
+            cipher.decrypt_autokey_verify(&encrypted, "QUEEN", plausible).unwrap(),
+            "ATTACK AT DAWN"
+        );
+        assert_eq!(
+            cipher.decrypt_autokey_verify(&encrypted, "QUEER", plausible),
+            Err(CipherError::ImplausiblePlaintext)
+        );
+    }
+    
+    #[test]
+    fn test_encrypt_lines() {
+        use std::io::Cursor;
+        
+        let cipher = StringCipher::uppercase_alpha();
+        let input = "ATTACK AT DAWN\n\nHOLD THE LINE!\r\nRETREAT";
+        let lines: Vec<Result<String, CipherError>> = cipher.encrypt_lines(Cursor::new(input), "LEMON").collect();
+        let expected: Vec<Result<String, CipherError>> = input
+            .lines()
+            .map(|line| cipher.encrypt(line, "LEMON"))
+            .collect();
+        assert_eq!(lines, expected);
+        
+        let errors: Vec<_> = cipher.encrypt_lines(Cursor::new(input), "").collect();
+        assert_eq!(errors, [Err(CipherError::EmptyKey)]);
+        
+        let mut invalid_utf8 = cipher.encrypt_lines(Cursor::new(b"AB\n\xff\nCD".to_vec()), "KEY");
+        assert!(invalid_utf8.next().unwrap().is_ok());
+        assert!(matches!(invalid_utf8.next(), Some(Err(CipherError::Io(_)))));
+        assert!(invalid_utf8.next().is_none());
+    }
+    
+    #[test]
+    fn test_charset_diff() {
+        let upper = StringCipher::uppercase_alpha();
+        let keyed = StringCipher::new("KEYWORDABCFGHIJLMNPQSTUVXZ").unwrap();
+        assert_eq!(
+            upper.diff(&keyed),
+            CharsetDiff { only_in_self: vec![], only_in_other: vec![], reordered: true }
+        );
+        assert_eq!(
+            upper.diff(&StringCipher::uppercase_alpha()),
+            CharsetDiff { only_in_self: vec![], only_in_other: vec![], reordered: false }
+        );
+        
+        // 只在末尾追加字符不影响已有字符的索引
+        let diff = upper.diff(&StringCipher::alphanumeric());
+        assert!(diff.only_in_self.is_empty());
+        assert_eq!(diff.only_in_other.len(), 36);
+        assert!(!diff.reordered);
+        
+        let diff = StringCipher::alphanumeric().diff(&upper);
+        assert_eq!(diff.only_in_self.len(), 36);
+        assert!(diff.only_in_other.is_empty());
+    }
+    
+    #[test]
+    fn test_encrypt_cow() {
+        let cipher = StringCipher::uppercase_alpha();
+        let punctuation = "... !?, -- 123";
+        let result = cipher.encrypt_cow(punctuation, "KEY").unwrap();
+        assert!(matches!(result, Cow::Borrowed(s) if s == punctuation));
+        assert!(matches!(cipher.encrypt_cow("", "KEY").unwrap(), Cow::Borrowed("")));
+        
+        let result = cipher.encrypt_cow("HELLO, WORLD!", "KEY").unwrap();
+        assert!(matches!(result, Cow::Owned(_)));
+        assert_eq!(result, cipher.encrypt("HELLO, WORLD!", "KEY").unwrap());
+        
+        assert_eq!(cipher.encrypt_cow(punctuation, ""), Err(CipherError::EmptyKey));
+    }
+    
+    #[test]
+    fn test_salted_round_trip() {
+        let cipher = StringCipher::uppercase_alpha();
+        let text = "MEET ME AT MIDNIGHT";
+        
+        let encrypted = cipher.encrypt_salted(text, "KEY", "SALT").unwrap();
+        assert_eq!(cipher.decrypt_salted(&encrypted, "KEY", "SALT").unwrap(), text);
+        assert_ne!(cipher.decrypt_salted(&encrypted, "KEY", "SALE").unwrap(), text);
+        
+        let other = cipher.encrypt_salted(text, "KEY", "PEPPER").unwrap();
+        assert_ne!(encrypted, other);
+        assert_eq!(cipher.encrypt_salted(text, "KEY", "").unwrap(), cipher.encrypt(text, "KEY").unwrap());
+        
+        assert_eq!(cipher.encrypt_salted(text, "KEY", "salt"), Err(CipherError::CharNotInCharset('s')));
+        assert_eq!(cipher.encrypt_salted(text, "", "SALT"), Err(CipherError::EmptyKey));
+    }
+    
+    #[test]
+    fn test_restrict_to() {
+        let digits = StringCipher::alphanumeric().restrict_to("0123456789").unwrap();
+        assert_eq!(digits.modulus(), 10);
+        assert_eq!(digits.encrypt("2024", "1").unwrap(), "3135");
+        
+        let reordered = StringCipher::alphanumeric().restrict_to("9876543210").unwrap();
+        assert!(!reordered.diff(&digits).reordered);
+        
+        assert_eq!(
+            StringCipher::uppercase_alpha().restrict_to("abc").unwrap_err(),
+            CipherError::EmptyCharset
+        );
+    }
+    
+    #[test]
+    fn test_decrypt_is_clean() {
+        let cipher = StringCipher::uppercase_alpha();
+        let ciphertext = cipher.encrypt("ATTACKATDAWN", "LEMON").unwrap();
+        assert_eq!(cipher.decrypt_is_clean(&ciphertext, "LEMON"), Ok(true));
+        assert_eq!(cipher.decrypt_is_clean("", "LEMON"), Ok(true));
+        
+        let corrupted = ciphertext.replacen(&ciphertext[3..4], "#", 1);
+        assert_eq!(cipher.decrypt_is_clean(&corrupted, "LEMON"), Ok(false));
+        assert_eq!(cipher.decrypt_is_clean("ATTACK AT DAWN", "LEMON"), Ok(false));
+        assert_eq!(cipher.decrypt_is_clean(&ciphertext, ""), Err(CipherError::EmptyKey));
+    }
+    
+    #[test]
+    fn test_vigenere_then_transpose_round_trip() {
+        let cipher = StringCipher::uppercase_alpha();
+        let text = "WE ARE DISCOVERED, FLEE AT ONCE!";
+        
+        let encrypted = cipher.encrypt_vigenere_then_transpose(text, "LEMON", "ZEBRAS").unwrap();
+        assert_ne!(encrypted, cipher.encrypt(text, "LEMON").unwrap());
+        // 非字符集字符留在原位
+        assert_eq!(encrypted.len(), text.len());
+        assert_eq!(&encrypted[2..3], " ");
+        assert!(encrypted.ends_with('!'));
+        // 换位只改变顺序，字符构成与普通维吉尼亚密文相同
+        let mut sorted: Vec<char> = encrypted.chars().collect();
+        let mut expected: Vec<char> = cipher.encrypt(text, "LEMON").unwrap().chars().collect();
+        sorted.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(sorted, expected);
+        
+        assert_eq!(cipher.decrypt_transpose_then_vigenere(&encrypted, "LEMON", "ZEBRAS").unwrap(), text);
+        assert_eq!(cipher.encrypt_vigenere_then_transpose(text, "LEMON", ""), Err(CipherError::EmptyKey));
+        assert_eq!(cipher.encrypt_vigenere_then_transpose(text, "", "ZEBRAS"), Err(CipherError::EmptyKey));
+    }
+    
+    #[test]
+    fn test_render_tableau() {
+        let cipher = StringCipher::uppercase_alpha();
+        let rendered = cipher.render_tableau();
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines.len(), 27);
+        assert_eq!(lines[0], "  A B C D E F G H I J K L M N O P Q R S T U V W X Y Z");
+        assert_eq!(lines[11], "K K L M N O P Q R S T U V W X Y Z A B C D E F G H I J");
+        
+        // 与加密结果一致
+        let table = cipher.tableau();
+        assert_eq!(table[10][7], cipher.encrypt("H", "K").unwrap().chars().next().unwrap());
+        
+        let large = StringCipher::printable_ascii().render_tableau();
+        assert_eq!(large.lines().count(), 66);
+        assert!(large.ends_with("（仅显示前 64 个字符，共 95 个）\n"));
+    }
+    
+    #[test]
+    fn test_sticky_key() {
+        let cipher = StringCipher::uppercase_alpha();
+        // "K*Y*" 的位移序列为 10, 10, 24, 24
+        assert_eq!(cipher.encrypt_sticky("AAAAAA", "K*Y*").unwrap(), "KKYYKK");
+        assert_eq!(cipher.encrypt_sticky("HELLO", "K*Y*").unwrap(), cipher.encrypt("HELLO", "KKYY").unwrap());
+        
+        let text = "STICKY KEYS REPEAT!";
+        let encrypted = cipher.encrypt_sticky(text, "LE*MON**").unwrap();
+        assert_eq!(cipher.decrypt_sticky(&encrypted, "LE*MON**").unwrap(), text);
+        
+        // 自定义哨兵，开头的哨兵位移为 0
+        assert_eq!(cipher.encrypt_sticky_with("AAAA", "?B?", '?').unwrap(), "ABBA");
+        assert_eq!(cipher.encrypt_sticky("AB", "*k"), Err(CipherError::CharNotInCharset('k')));
+        assert_eq!(cipher.encrypt_sticky("AB", ""), Err(CipherError::EmptyKey));
+    }
+    
+    #[test]
+    fn test_encrypt_per_word() {
+        let cipher = StringCipher::uppercase_alpha();
+        let encrypted = cipher.encrypt_per_word("HELLO WORLD", "KEY").unwrap();
+        // 两个词都从密钥第 0 位开始
+        assert_eq!(encrypted, "RIJVS GSPVH");
+        assert_eq!(&encrypted[6..], cipher.encrypt("WORLD", "KEY").unwrap());
+        assert_ne!(encrypted, cipher.encrypt("HELLO WORLD", "KEY").unwrap());
+        
+        let text = "ONE, TWO...  THREE!";
+        let encrypted = cipher.encrypt_per_word(text, "LEMON").unwrap();
+        assert_eq!(cipher.decrypt_per_word(&encrypted, "LEMON").unwrap(), text);
+    }
+    
+    #[test]
+    fn test_explain_char() {
+        let cipher = StringCipher::uppercase_alpha();
+        assert_eq!(cipher.explain_char('A'), CharStatus::InCharset(0));
+        assert_eq!(cipher.explain_char('Z'), CharStatus::InCharset(25));
+        assert_eq!(cipher.explain_char('h'), CharStatus::CaseMismatch('H'));
+        assert_eq!(cipher.explain_char('7'), CharStatus::NotInCharset);
+        assert_eq!(cipher.explain_char(' '), CharStatus::NotInCharset);
+        assert_eq!(cipher.explain_char('é'), CharStatus::NotInCharset);
+        
+        assert_eq!(StringCipher::mixed_alpha().explain_char('h'), CharStatus::InCharset(33));
+    }
+    
+    #[test]
+    fn test_encrypt_with_consumed() {
+        let cipher = StringCipher::uppercase_alpha();
+        let (encrypted, next) = cipher.encrypt_with_consumed("ABC", "LEMON").unwrap();
+        assert_eq!(encrypted, cipher.encrypt("ABC", "LEMON").unwrap());
+        assert_eq!(next, 3);
+        
+        // 密钥用完一轮后回到对应位置
+        assert_eq!(cipher.encrypt_with_consumed("HELLO, WORLD", "LEMON").unwrap().1, 0);
+        assert_eq!(cipher.encrypt_with_consumed("HELLO, WORLD!", "KEY").unwrap().1, 1);
+        assert_eq!(cipher.encrypt_with_consumed("...", "KEY").unwrap(), ("...".to_string(), 0));
+        assert_eq!(cipher.encrypt_with_consumed("ABC", ""), Err(CipherError::EmptyKey));
+    }
     
     #[test]
-    fn test_string_basic_encryption() {
+    fn test_token_round_trip() {
+        for (name, _, cipher) in StringCipher::presets() {
+            let token = cipher.to_token();
+            assert!(token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'), "{name}");
+            let restored = StringCipher::from_token(&token).unwrap();
+            assert_eq!(restored.charset_info(), cipher.charset_info());
+        }
+        
+        let greek = StringCipher::new("αβγδ").unwrap();
+        let restored = StringCipher::from_token(&greek.to_token()).unwrap();
+        assert_eq!(restored.encrypt("αγ", "β").unwrap(), greek.encrypt("αγ", "β").unwrap());
+        
+        assert_eq!(StringCipher::from_token("not a token").unwrap_err(), CipherError::InvalidToken);
+        assert_eq!(StringCipher::from_token("_w").unwrap_err(), CipherError::InvalidToken); // 0xff
+        assert_eq!(StringCipher::from_token("").unwrap_err(), CipherError::EmptyCharset);
+    }
+    
+    #[test]
+    fn test_prepared_key_matches_encrypt() {
+        let cipher = StringCipher::printable_ascii();
+        let long_key: String = (32u8..=126).cycle().take(1000).map(char::from).collect();
+        let key = cipher.prepare_key(&long_key).unwrap();
+        assert_eq!(key.len(), 1000);
+        
+        for text in ["Hello, World!", "", "日本語 mixed text ~"] {
+            let encrypted = cipher.encrypt_prepared(text, &key).unwrap();
+            assert_eq!(encrypted, cipher.encrypt(text, &long_key).unwrap());
+            assert_eq!(cipher.decrypt_prepared(&encrypted, &key).unwrap(), text);
+        }
+        
+        // 字符集相同的另一个密码器可以使用，字符集不同（即使大小相同）则拒绝
+        let same = StringCipher::printable_ascii();
+        assert_eq!(same.encrypt_prepared("Hi", &key).unwrap(), cipher.encrypt("Hi", &long_key).unwrap());
+        let upper_key = StringCipher::uppercase_alpha().prepare_key("ZZZ").unwrap();
+        assert_eq!(cipher.encrypt_prepared("Hi", &upper_key).unwrap_err(), CipherError::PreparedKeyMismatch);
+        assert_eq!(cipher.decrypt_prepared("Hi", &upper_key).unwrap_err(), CipherError::PreparedKeyMismatch);
+        let reversed: String = ('A'..='Z').rev().collect();
+        let reversed_key = StringCipher::new(&reversed).unwrap().prepare_key("KEY").unwrap();
+        assert_eq!(
+            StringCipher::uppercase_alpha().encrypt_prepared("HELLO", &reversed_key).unwrap_err(),
+            CipherError::PreparedKeyMismatch
+        );
+        
+        assert_eq!(cipher.prepare_key("").unwrap_err(), CipherError::EmptyKey);
+        assert_eq!(cipher.prepare_key("é").unwrap_err(), CipherError::CharNotInCharset('é'));
+    }
+    
+    #[test]
+    fn test_encrypt_nontrivial() {
+        let cipher = StringCipher::uppercase_alpha();
+        // 只有第一个密钥字符参与加密，且它是索引 0 的字符
+        assert_eq!(cipher.encrypt_nontrivial("H", "AKEY"), Err(CipherError::IdentityEncryption));
+        assert_eq!(cipher.encrypt_nontrivial("HI", "AKEY").unwrap(), "HS");
+        assert_eq!(cipher.encrypt_nontrivial("123!", "KEY"), Err(CipherError::IdentityEncryption));
+        assert_eq!(cipher.encrypt_nontrivial("H", ""), Err(CipherError::EmptyKey));
+    }
+    
+    #[test]
+    fn test_decrypt_legacy() {
+        let cipher = StringCipher::uppercase_alpha();
+        
+        // 按当前语义手工计算：标点和空格不消耗密钥
+        // H+K=R I+E=M , 空格 T+Y=R H+K=R E+E=I R+Y=P E+K=O
+        assert_eq!(cipher.encrypt("HI, THERE", "KEY").unwrap(), "RM, RRIPO");
+        assert_eq!(cipher.decrypt_legacy("RM, RRIPO", "KEY").unwrap(), "HI, THERE");
+        
+        let text = "MEET AT NOON, BY THE OLD OAK TREE!";
+        let encrypted = cipher.encrypt(text, "LEMON").unwrap();
+        assert_eq!(cipher.decrypt_legacy(&encrypted, "LEMON").unwrap(), text);
+        assert_eq!(cipher.decrypt_legacy("K, E", ""), Err(CipherError::EmptyKey));
+    }
+    
+    #[test]
+    fn test_decrypt_legacy_differs_from_positional() {
+        let cipher = StringCipher::uppercase_alpha();
+        
+        // 严格按位置对齐时每个字符（含标点和空格）都消耗密钥位置，手工计算：
+        // H+K=R I+E=M ,(Y) 空格(K) T+E=X H+Y=F E+K=O R+E=V E+Y=C
+        let positional = "RM, XFOVC";
+        assert_ne!(positional, cipher.encrypt("HI, THERE", "KEY").unwrap());
+        // 兼容语义无法解开按位置对齐的密文：分隔符之后密钥就错位了
+        let decrypted = cipher.decrypt_legacy(positional, "KEY").unwrap();
+        assert_eq!(&decrypted[..4], "HI, ");
+        assert_ne!(decrypted, "HI, THERE");
+        
+        // 没有分隔符时两种对齐方式一致
+        assert_eq!(cipher.decrypt_legacy("RMRRIPO", "KEY").unwrap(), "HITHERE");
+    }
+    
+    #[test]
+    fn test_rotations() {
+        let cipher = StringCipher::uppercase_alpha();
+        let rotations: Vec<String> = cipher.rotations().collect();
+        assert_eq!(rotations.len(), cipher.modulus());
+        assert_eq!(rotations[0], "ABCDEFGHIJKLMNOPQRSTUVWXYZ");
+        assert_eq!(rotations[3], "DEFGHIJKLMNOPQRSTUVWXYZABC");
+        
+        // 每个循环移位都是合法的字符集，且与方阵对应行一致
+        for (rotation, row) in rotations.iter().zip(cipher.tableau()) {
+            assert_eq!(*rotation, row.into_iter().collect::<String>());
+            assert_eq!(StringCipher::new(rotation).unwrap().modulus(), 26);
+        }
+    }
+    
+    #[test]
+    fn test_bytes_key() {
         let cipher = StringCipher::uppercase_alpha();
+        let key = [10u8, 4, 24 + 26 * 8];
+        let encrypted = cipher.encrypt_bytes_key("HELLO, WORLD!", &key).unwrap();
+        assert_eq!(encrypted, cipher.encrypt("HELLO, WORLD!", "KEY").unwrap());
+        assert_eq!(cipher.decrypt_bytes_key(&encrypted, &key).unwrap(), "HELLO, WORLD!");
+        
+        assert_eq!(cipher.encrypt_bytes_key("HELLO", b"key").unwrap(), "KBCOL");
+        assert_eq!(cipher.encrypt_bytes_key("HELLO", &[]), Err(CipherError::EmptyKey));
+        assert_eq!(cipher.decrypt_bytes_key("HELLO", &[]), Err(CipherError::EmptyKey));
+    }
+    
+    #[test]
+    fn test_fixed_points() {
+        let cipher = StringCipher::uppercase_alpha();
+        assert_eq!(cipher.fixed_points("AKEY", 9).unwrap(), 3);
+        assert_eq!(cipher.fixed_points("AKEY", 8).unwrap(), 2);
+        assert_eq!(cipher.fixed_points("KEY", 1000).unwrap(), 0);
+        assert_eq!(cipher.fixed_points("AA", 5).unwrap(), 5);
+        assert_eq!(cipher.fixed_points("AKEY", 0).unwrap(), 0);
+        assert_eq!(cipher.fixed_points("", 5), Err(CipherError::EmptyKey));
+        
+        // 与实际加密结果一致
+        let text = "HELLOWORLD";
+        let encrypted = cipher.encrypt(text, "BAD").unwrap();
+        let unchanged = text.chars().zip(encrypted.chars()).filter(|(p, c)| p == c).count();
+        assert_eq!(cipher.fixed_points("BAD", text.len()).unwrap(), unchanged);
+    }
+    
+    #[test]
+    fn test_with_permutation() {
+        let alphabet = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let identity: Vec<usize> = (0..26).collect();
+        let plain = StringCipher::with_permutation(alphabet, &identity).unwrap();
+        assert_eq!(plain.encrypt("HELLO", "KEY").unwrap(), "RIJVS");
+        
+        let reversed: Vec<usize> = (0..26).rev().collect();
+        let cipher = StringCipher::with_permutation(alphabet, &reversed).unwrap();
+        assert_eq!(cipher.explain_char('Z'), CharStatus::InCharset(0));
         let encrypted = cipher.encrypt("HELLO", "KEY").unwrap();
-        assert_eq!(encrypted, "RIJVS");
+        assert_ne!(encrypted, "RIJVS");
+        assert_eq!(cipher.decrypt(&encrypted, "KEY").unwrap(), "HELLO");
+        
+        assert_eq!(StringCipher::with_permutation("ABC", &[0, 1]).unwrap_err(), CipherError::InvalidPermutation);
+        assert_eq!(StringCipher::with_permutation("ABC", &[0, 1, 1]).unwrap_err(), CipherError::InvalidPermutation);
+        assert_eq!(StringCipher::with_permutation("ABC", &[0, 1, 3]).unwrap_err(), CipherError::InvalidPermutation);
+        assert_eq!(StringCipher::with_permutation("ABA", &[0, 1, 2]).unwrap_err(), CipherError::DuplicateChar('A'));
     }
-
+    
     #[test]
-    fn test_string_basic_decryption() {
+    fn test_find_duplicates() {
+        assert_eq!(StringCipher::find_duplicates("ABCA B"), vec![('A', vec![0, 3]), ('B', vec![1, 5])]);
+        assert_eq!(StringCipher::find_duplicates("ABCA"), vec![('A', vec![0, 3])]);
+        assert_eq!(
+            StringCipher::find_duplicates("xyzzyx!"),
+            vec![('x', vec![0, 5]), ('y', vec![1, 4]), ('z', vec![2, 3])]
+        );
+        assert_eq!(StringCipher::find_duplicates("ééé"), vec![('é', vec![0, 1, 2])]);
+        assert!(StringCipher::find_duplicates("").is_empty());
+        assert!(StringCipher::find_duplicates("ABCDEFGHIJKLMNOPQRSTUVWXYZ").is_empty());
+    }
+    
+    #[test]
+    fn test_merge() {
+        let digits = StringCipher::new("0123456789").unwrap();
+        let merged = StringCipher::uppercase_alpha().merge(&digits).unwrap();
+        assert_eq!(merged.modulus(), 36);
+        assert_eq!(
+            merged.charset_info(),
+            StringCipher::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789").unwrap().charset_info()
+        );
+        assert!(!StringCipher::uppercase_alpha().diff(&merged).reordered);
+        
+        // 已有的字符不会重复加入
+        let again = merged.merge(&StringCipher::alphanumeric()).unwrap();
+        assert_eq!(again.modulus(), 62);
+        assert_eq!(again.explain_char('0'), CharStatus::InCharset(26));
+    }
+    
+    #[test]
+    fn test_covers_message() {
         let cipher = StringCipher::uppercase_alpha();
-        let decrypted = cipher.decrypt("RIJVS", "KEY").unwrap();
-        assert_eq!(decrypted, "HELLO");
+        assert_eq!(cipher.covers_message("KEY", "HELLO"), Ok(false));
+        assert_eq!(cipher.covers_message("KEYXY", "HELLO"), Ok(true));
+        assert_eq!(cipher.covers_message("KEY", "H-I-!"), Ok(true));
+        assert_eq!(cipher.covers_message("KEY", ""), Ok(true));
+        assert_eq!(cipher.covers_message("", "HELLO"), Err(CipherError::EmptyKey));
     }
-
+    
     #[test]
-    fn test_string_round_trip() {
+    fn test_base_n_cipher() {
+        let hex = base_n_cipher(16).unwrap();
+        assert_eq!(hex.modulus(), 16);
+        let encrypted = hex.encrypt("DEADBEEF", "C0FFEE").unwrap();
+        assert_eq!(encrypted, "9E9C9CAF");
+        assert_eq!(hex.decrypt(&encrypted, "C0FFEE").unwrap(), "DEADBEEF");
+        // 小写十六进制不在字符集中
+        assert_eq!(hex.encrypt("ff", "1").unwrap(), "ff");
+        
+        assert_eq!(base_n_cipher(2).unwrap().encrypt("0101", "1").unwrap(), "1010");
+        assert_eq!(base_n_cipher(36).unwrap().modulus(), 36);
+        assert_eq!(base_n_cipher(1).unwrap_err(), CipherError::InvalidBase(1));
+        assert_eq!(base_n_cipher(37).unwrap_err(), CipherError::InvalidBase(37));
+    }
+    
+    #[test]
+    fn test_decrypt_strict() {
+        let cipher = StringCipher::uppercase_alpha();
+        let encrypted = cipher.encrypt("ATTACKATDAWN", "LEMON").unwrap();
+        assert_eq!(cipher.decrypt_strict(&encrypted, "LEMON").unwrap(), "ATTACKATDAWN");
+        assert_eq!(cipher.decrypt_strict("", "LEMON").unwrap(), "");
+        
+        assert_eq!(
+            cipher.decrypt_strict("RIJV?S", "KEY"),
+            Err(CipherError::InvalidCiphertextChar { ch: '?', position: 4 })
+        );
+        // 报告第一个无效字符
+        assert_eq!(
+            cipher.decrypt_strict("R jV", "KEY"),
+            Err(CipherError::InvalidCiphertextChar { ch: ' ', position: 1 })
+        );
+        assert_eq!(cipher.decrypt_strict("RIJVS", ""), Err(CipherError::EmptyKey));
+    }
+    
+    #[test]
+    fn test_wrap_output() {
+        let text: String = ('A'..='Z').chain('a'..='x').collect();
+        assert_eq!(text.chars().count(), 50);
+        let wrapped = wrap_output(&text, 10);
+        let lines: Vec<&str> = wrapped.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines.iter().all(|line| line.chars().count() == 10));
+        assert!(!wrapped.ends_with('\n'));
+        
+        assert_eq!(wrap_output("αβγδε", 2), "αβ\nγδ\nε");
+        assert_eq!(wrap_output("ABC", 0), "ABC");
+        assert_eq!(wrap_output("", 4), "");
+    }
+    
+    #[cfg(feature = "csv")]
+    #[test]
+    fn test_encrypt_csv_column() {
+        let cipher = StringCipher::uppercase_alpha();
+        let input = "id,name,city\n1,ALICE,PARIS\n2,\"BOB, JR\",ROME\n";
+        let mut output = Vec::new();
+        
+        let rows = cipher.encrypt_csv(input.as_bytes(), &mut output, "KEY", 1).unwrap();
+        assert_eq!(rows, 3);
+        
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], format!("id,{},city", cipher.encrypt("name", "KEY").unwrap()));
+        assert_eq!(lines[1], format!("1,{},PARIS", cipher.encrypt("ALICE", "KEY").unwrap()));
+        // 含逗号的单元格保持引号，其他列不变
+        assert_eq!(lines[2], format!("2,\"{}\",ROME", cipher.encrypt("BOB, JR", "KEY").unwrap()));
+        
+        assert_eq!(
+            cipher.encrypt_csv(input.as_bytes(), Vec::new(), "", 1).unwrap_err(),
+            CipherError::EmptyKey
+        );
+    }
+    
+    #[test]
+    fn test_encrypt_masked() {
+        let cipher = StringCipher::uppercase_alpha();
+        let full = cipher.encrypt("ATTACK AT DAWN", "LEMON").unwrap();
+        let masked = cipher.encrypt_masked("ATTACK AT DAWN", "LEMON", 2).unwrap();
+        
+        assert_eq!(&masked[..2], &full[..2]);
+        assert_eq!(masked, format!("{}**** ** ****", &full[..2]));
+        
+        assert_eq!(cipher.encrypt_masked_with("ABC", "B", 0, '#').unwrap(), "###");
+        assert_eq!(cipher.encrypt_masked("ABC", "B", 10).unwrap(), "BCD");
+        assert_eq!(cipher.encrypt_masked("ABC", "", 1).unwrap_err(), CipherError::EmptyKey);
+    }
+    
+    #[test]
+    fn test_coverage() {
+        let upper = StringCipher::uppercase_alpha();
+        assert_eq!(upper.coverage("hello world"), 0.0);
+        assert_eq!(upper.coverage("HELLO"), 1.0);
+        assert_eq!(upper.coverage("Hi"), 0.5);
+        assert_eq!(upper.coverage(""), 0.0);
+        
+        assert_eq!(StringCipher::printable_ascii().coverage("hello world"), 1.0);
+    }
+    
+    #[test]
+    fn test_encrypt_auto_widen() {
+        let cipher = StringCipher::uppercase_alpha();
+        let (encrypted, widened) = cipher.encrypt_auto_widen("Hello", "KEY").unwrap();
+        
+        // 小写字母 e、l、o 按首次出现顺序追加到字符集末尾
+        assert_eq!(widened.modulus(), 29);
+        assert_eq!(widened.coverage("Hello"), 1.0);
+        assert_ne!(encrypted, cipher.encrypt("Hello", "KEY").unwrap());
+        assert_eq!(widened.decrypt(&encrypted, "KEY").unwrap(), "Hello");
+        
+        // 已完全覆盖时字符集不变
+        let (encrypted, same) = cipher.encrypt_auto_widen("HELLO", "KEY").unwrap();
+        assert_eq!(same.modulus(), 26);
+        assert_eq!(encrypted, cipher.encrypt("HELLO", "KEY").unwrap());
+        
+        assert_eq!(cipher.encrypt_auto_widen("abc", "").unwrap_err(), CipherError::EmptyKey);
+    }
+    
+    #[test]
+    fn test_encrypt_until_timeout() {
+        let cipher = StringCipher::uppercase_alpha();
+        let text = "ATTACKATDAWN".repeat(10_000);
+        
+        let elapsed = Instant::now();
+        assert_eq!(
+            cipher.encrypt_until(&text, "LEMON", elapsed).unwrap_err(),
+            CipherError::Timeout { processed: 0 }
+        );
+        
+        let deadline = Instant::now() + Duration::from_secs(60);
+        assert_eq!(
+            cipher.encrypt_until(&text, "LEMON", deadline).unwrap(),
+            cipher.encrypt(&text, "LEMON").unwrap()
+        );
+    }
+    
+    #[test]
+    fn test_encrypt_chunked_matches_encrypt() {
+        let cipher = StringCipher::uppercase_alpha();
+        let text = "ATTACK AT DAWN, RETREAT AT DUSK";
+        let expected = cipher.encrypt(text, "LEMON").unwrap();
+        
+        for chunk_size in [1, 3, 7, 100] {
+            let chunks: Vec<String> = cipher.encrypt_chunked(text, "LEMON", chunk_size).unwrap().collect();
+            assert!(chunks.iter().all(|chunk| chunk.chars().count() <= chunk_size));
+            assert_eq!(chunks.concat(), expected);
+        }
+        
+        assert_eq!(cipher.encrypt_chunked("", "LEMON", 4).unwrap().count(), 0);
+        assert!(matches!(cipher.encrypt_chunked(text, "", 4), Err(CipherError::EmptyKey)));
+    }
+    
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_chaff_round_trip() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        
+        let cipher = StringCipher::uppercase_alpha();
+        let text = "ATTACK AT DAWN";
+        let mut rng = StdRng::seed_from_u64(2024);
+        
+        let noisy = cipher.encrypt_with_chaff(text, "LEMON", "#@!", 0.5, &mut rng).unwrap();
+        assert!(noisy.chars().count() > text.chars().count());
+        assert_eq!(cipher.decrypt_strip_chaff(&noisy, "LEMON", "#@!").unwrap(), text);
+        
+        // 普通解密保留干扰字符，但密钥位置不受影响
+        let plain = cipher.decrypt(&noisy, "LEMON").unwrap();
+        let without_chaff: String = plain.chars().filter(|ch| !"#@!".contains(*ch)).collect();
+        assert_eq!(without_chaff, text);
+        
+        // 概率为 0 或干扰字符为空时不插入
+        let encrypted = cipher.encrypt(text, "LEMON").unwrap();
+        assert_eq!(cipher.encrypt_with_chaff(text, "LEMON", "#", 0.0, &mut rng).unwrap(), encrypted);
+        assert_eq!(cipher.encrypt_with_chaff(text, "LEMON", "", 1.0, &mut rng).unwrap(), encrypted);
+        
+        assert_eq!(
+            cipher.encrypt_with_chaff(text, "LEMON", "#Q", 0.5, &mut rng).unwrap_err(),
+            CipherError::ChaffInCharset('Q')
+        );
+        
+        // 超出范围的有限概率被限制，NaN 与无穷大被拒绝
+        assert_eq!(cipher.encrypt_with_chaff(text, "LEMON", "#", -2.0, &mut rng).unwrap(), encrypted);
+        for rate in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert_eq!(
+                cipher.encrypt_with_chaff(text, "LEMON", "#", rate, &mut rng).unwrap_err(),
+                CipherError::InvalidRate
+            );
+        }
+    }
+    
+    #[test]
+    fn test_decrypt_strip_chaff() {
+        let cipher = StringCipher::uppercase_alpha();
+        assert_eq!(cipher.decrypt_strip_chaff("#RI#JV%S", "KEY", "#%").unwrap(), "HELLO");
+        assert_eq!(
+            cipher.decrypt_strip_chaff("RIJVS", "KEY", "A").unwrap_err(),
+            CipherError::ChaffInCharset('A')
+        );
+    }
+    
+    #[test]
+    fn test_digit_cipher_carry() {
+        let cipher = DigitCipher::new();
+        
+        // 9 + 1 产生进位，连续的 9 使进位一路传递
+        assert_eq!(cipher.encrypt_with_carry("09", "01").unwrap(), "10");
+        assert_eq!(cipher.encrypt_with_carry("0999", "0001").unwrap(), "1000");
+        // 最高位的进位被丢弃
+        assert_eq!(cipher.encrypt_with_carry("99", "01").unwrap(), "00");
+        // 逐位维吉尼亚则不会进位
+        assert_eq!(StringCipher::new("0123456789").unwrap().encrypt("09", "01").unwrap(), "00");
+        
+        // 密钥循环扩展：123456 + 404040
+        assert_eq!(cipher.encrypt_with_carry("123456", "40").unwrap(), "527496");
+        
+        for (digits, key) in [("09", "01"), ("0999", "0001"), ("99", "01"), ("31415926", "271")] {
+            let encrypted = cipher.encrypt_with_carry(digits, key).unwrap();
+            assert_eq!(cipher.decrypt_with_carry(&encrypted, key).unwrap(), digits);
+        }
+        
+        assert_eq!(cipher.encrypt_with_carry("12", "").unwrap_err(), CipherError::EmptyKey);
+        assert_eq!(cipher.encrypt_with_carry("1a", "1").unwrap_err(), CipherError::CharNotInCharset('a'));
+    }
+    
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_encrypt_json_fields() {
+        let cipher = StringCipher::uppercase_alpha();
+        let json = cipher.encrypt_json("ATTACK \"AT\" DAWN", "ABAB").unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        
+        assert_eq!(value["ciphertext"], cipher.encrypt("ATTACK \"AT\" DAWN", "ABAB").unwrap());
+        assert_eq!(value["charset_size"], 26);
+        assert_eq!(value["key_period"], 2);
+        
+        assert_eq!(cipher.encrypt_json("HELLO", "").unwrap_err(), CipherError::EmptyKey);
+    }
+    
+    #[test]
+    fn test_key_period() {
+        let cipher = StringCipher::uppercase_alpha();
+        let period = |key: &str| key_period(&cipher.parse_key(key).unwrap());
+        assert_eq!(period("AAAA"), 1);
+        assert_eq!(period("ABAB"), 2);
+        assert_eq!(period("ABA"), 3);
+        assert_eq!(period("KEYKEY"), 3);
+    }
+    
+    #[test]
+    fn test_encrypt_checked_warnings() {
+        let cipher = StringCipher::uppercase_alpha();
+        
+        let (encrypted, warnings) = cipher.encrypt_checked("HELLO WORLD", "SECRETKEY").unwrap();
+        assert_eq!(encrypted, cipher.encrypt("HELLO WORLD", "SECRETKEY").unwrap());
+        assert!(warnings.is_empty());
+        
+        let (_, warnings) = cipher.encrypt_checked("HELLO WORLD", "KEY").unwrap();
+        assert_eq!(warnings, [Warning::ShortKey]);
+        
+        // 12 个字母恰好是密钥长度 6 的两倍
+        let (_, warnings) = cipher.encrypt_checked("ATTACK AT DAWN", "CIPHER").unwrap();
+        assert_eq!(warnings, [Warning::KeyLenDividesTextLen]);
+        
+        let (_, warnings) = cipher.encrypt_checked("HELLO WORLD", "ABCABCABC").unwrap();
+        assert_eq!(warnings, [Warning::RepetitiveKey]);
+        
+        let (_, warnings) = cipher.encrypt_checked("ABCD", "AA").unwrap();
+        assert_eq!(warnings, [Warning::ShortKey, Warning::KeyLenDividesTextLen, Warning::RepetitiveKey]);
+        
+        assert_eq!(cipher.encrypt_checked("ABC", "").unwrap_err(), CipherError::EmptyKey);
+    }
+    
+    #[test]
+    fn test_keyspace() {
+        let cipher = StringCipher::uppercase_alpha();
+        assert_eq!(cipher.keyspace(3), Some(17576));
+        assert_eq!(cipher.keyspace(0), Some(1));
+        assert_eq!(cipher.keyspace(27), Some(26u128.pow(27)));
+        assert_eq!(cipher.keyspace(28), None);
+        assert_eq!(cipher.keyspace(1000), None);
+        assert_eq!(cipher.keyspace(usize::MAX), None);
+    }
+    
+    #[test]
+    fn test_encrypt_where_letters_only() {
         let cipher = StringCipher::alphanumeric();
-        let original = "Hello123World";
-        let encrypted = cipher.encrypt(original, "SecretKey").unwrap();
-        let decrypted = cipher.decrypt(&encrypted, "SecretKey").unwrap();
-        assert_eq!(original, decrypted);
+        let is_letter = |c: char| c.is_ascii_alphabetic();
+        
+        let encrypted = cipher.encrypt_where("ABC123", "B", is_letter).unwrap();
+        assert_eq!(encrypted, "BCD123");
+        // 不加条件时数字也在字符集中，会被加密
+        assert_ne!(cipher.encrypt("ABC123", "B").unwrap(), encrypted);
+        
+        // 跳过的字符不推进密钥位置
+        let skipped = cipher.encrypt_where("A1B2C3", "BC", is_letter).unwrap();
+        assert_eq!(skipped, "B1D2D3");
+        assert_eq!(cipher.decrypt_where(&skipped, "BC", is_letter).unwrap(), "A1B2C3");
+        
+        assert_eq!(cipher.encrypt_where("ABC", "", is_letter).unwrap_err(), CipherError::EmptyKey);
     }
-
+    
     #[test]
-    fn test_string_custom_charset() {
-        let cipher = StringCipher::new("0123456789").unwrap();
-        let encrypted = cipher.encrypt("123", "456").unwrap();
-        assert_eq!(encrypted, "579");
+    fn test_decryptor_closure() {
+        let cipher = StringCipher::uppercase_alpha();
+        let messages = ["HELLO", "ATTACK AT DAWN", "", "123"];
+        let ciphertexts: Vec<String> = messages.iter().map(|m| cipher.encrypt(m, "LEMON").unwrap()).collect();
+        
+        // 闭包不借用密钥字符串，可以在密钥被释放后继续使用
+        let decrypt = {
+            let key = String::from("LEMON");
+            cipher.decryptor(&key).unwrap()
+        };
+        let decrypted: Vec<String> = ciphertexts.iter().map(|c| decrypt(c)).collect();
+        assert_eq!(decrypted, messages);
+        
+        assert!(matches!(cipher.decryptor(""), Err(CipherError::EmptyKey)));
+        assert!(matches!(cipher.decryptor("lemon"), Err(CipherError::CharNotInCharset('l'))));
     }
-
+    
     #[test]
-    fn test_string_preserve_unknown_chars() {
+    fn test_fibonacci_shifts() {
         let cipher = StringCipher::uppercase_alpha();
-        let encrypted = cipher.encrypt("HELLO, WORLD!", "KEY").unwrap();
-        // 逗号、空格、感叹号保持不变
-        assert_eq!(encrypted, "RIJVS, UYVJN!");
+        let shifts = |a, b| -> Vec<usize> {
+            cipher
+                .to_elements(&cipher.encrypt_fibonacci(&"A".repeat(10), a, b).unwrap())
+                .iter()
+                .map(|e| e.index())
+                .collect()
+        };
+        
+        assert_eq!(shifts(0, 1), [0, 1, 1, 2, 3, 5, 8, 13, 21, 8]);
+        assert_eq!(shifts(2, 1), [2, 1, 3, 4, 7, 11, 18, 3, 21, 24]);
+        // 种子先对模数取模
+        assert_eq!(shifts(26, 27), shifts(0, 1));
+        
+        let text = "ATTACK AT DAWN!";
+        let encrypted = cipher.encrypt_fibonacci(text, 3, 7).unwrap();
+        assert_ne!(encrypted, text);
+        assert_eq!(cipher.decrypt_fibonacci(&encrypted, 3, 7).unwrap(), text);
+        assert_ne!(cipher.decrypt_fibonacci(&encrypted, 7, 3).unwrap(), text);
     }
-
+    
     #[test]
-    fn test_string_empty_key_error() {
+    fn test_is_ciphertext_of() {
         let cipher = StringCipher::uppercase_alpha();
-        assert!(cipher.encrypt("HELLO", "").is_err());
+        let ciphertext = cipher.encrypt("ATTACK AT DAWN", "LEMON").unwrap();
+        
+        assert!(cipher.is_ciphertext_of("ATTACK AT DAWN", &ciphertext, "LEMON").unwrap());
+        assert!(!cipher.is_ciphertext_of("ATTACK AT DUSK", &ciphertext, "LEMON").unwrap());
+        assert!(!cipher.is_ciphertext_of("ATTACK AT DAWN", &ciphertext, "LIME").unwrap());
+        assert!(!cipher.is_ciphertext_of("ATTACK AT DAWN", &ciphertext[1..], "LEMON").unwrap());
+        assert_eq!(cipher.is_ciphertext_of("A", "B", "").unwrap_err(), CipherError::EmptyKey);
     }
-
+    
     #[test]
-    fn test_string_invalid_key_char() {
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"", b""));
+        assert!(constant_time_eq(b"abc", b"abc"));
+        assert!(!constant_time_eq(b"abc", b"abd"));
+        assert!(!constant_time_eq(b"abc", b"ab"));
+    }
+    
+    #[test]
+    fn test_encrypt_with_keyfile() {
+        use std::io::Cursor;
+        
         let cipher = StringCipher::uppercase_alpha();
-        assert!(cipher.encrypt("HELLO", "key").is_err()); // 小写字母不在大写字符集中
+        let key_source = b"It was the best of times, it was the worst of times".to_vec();
+        let text = "ATTACK AT DAWN";
+        
+        let encrypted = cipher.encrypt_with_keyfile(text, Cursor::new(&key_source)).unwrap();
+        assert_eq!(cipher.decrypt_with_keyfile(&encrypted, Cursor::new(&key_source)).unwrap(), text);
+        
+        // 密钥不循环：与使用等长字节密钥的结果相同
+        assert_eq!(encrypted, cipher.encrypt_bytes_key(text, &key_source[..12]).unwrap());
+        
+        // 12 个字母只需 12 个密钥字节
+        let mut reader = Cursor::new(&key_source);
+        cipher.encrypt_with_keyfile(text, &mut reader).unwrap();
+        assert_eq!(reader.position(), 12);
+        
+        assert_eq!(
+            cipher.encrypt_with_keyfile(text, Cursor::new(b"SHORT")).unwrap_err(),
+            CipherError::KeyExhausted { needed: 12, available: 5 }
+        );
     }
-
+    
     #[test]
-    fn test_string_duplicate_charset_error() {
-        let result = StringCipher::new("ABBA");
-        assert!(result.is_err());
+    fn test_cipher_view() {
+        for cipher in [StringCipher::uppercase_alpha(), StringCipher::printable_ascii()] {
+            let view = cipher.view();
+            assert_eq!(view.modulus(), cipher.modulus());
+            assert_eq!(view.charset().len(), view.modulus());
+            assert!(view.charset().iter().enumerate().all(|(i, elem)| elem.index() == i));
+        }
+    }
+    
+    #[cfg(not(feature = "color"))]
+    #[test]
+    fn test_encrypt_colored_disabled() {
+        let cipher = StringCipher::uppercase_alpha();
+        let text = "ATTACK AT DAWN!";
+        assert_eq!(cipher.encrypt_colored(text, "LEMON").unwrap(), cipher.encrypt(text, "LEMON").unwrap());
+        assert_eq!(cipher.encrypt_colored(text, "").unwrap_err(), CipherError::EmptyKey);
+    }
+    
+    #[cfg(feature = "color")]
+    #[test]
+    fn test_encrypt_colored_ansi() {
+        let cipher = StringCipher::uppercase_alpha();
+        let text = "ATTACK AT DAWN!";
+        let colored = cipher.encrypt_colored(text, "LEMON").unwrap();
+        
+        // 去掉转义序列后与普通密文相同
+        let mut plain = String::new();
+        let mut in_escape = false;
+        for ch in colored.chars() {
+            match ch {
+                '\x1b' => in_escape = true,
+                'm' if in_escape => in_escape = false,
+                _ if !in_escape => plain.push(ch),
+                _ => {}
+            }
+        }
+        assert_eq!(plain, cipher.encrypt(text, "LEMON").unwrap());
+        
+        // 空格和标点以暗色显示，第 0 个密钥位置为红色
+        assert!(colored.contains("\x1b[2m \x1b[0m"));
+        assert!(colored.contains("\x1b[2m!\x1b[0m"));
+        assert!(colored.starts_with("\x1b[31mL\x1b[0m"));
+    }
+    
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_shuffled_deterministic() {
+        let base = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let chars = |cipher: &StringCipher| -> Vec<char> { cipher.charset.iter().map(|e| e.value()).collect() };
+        
+        let a = StringCipher::shuffled(base, 2024).unwrap();
+        let b = StringCipher::shuffled(base, 2024).unwrap();
+        let c = StringCipher::shuffled(base, 2025).unwrap();
+        assert_eq!(chars(&a), chars(&b));
+        assert_ne!(chars(&a), chars(&c));
+        assert_ne!(chars(&a), base.chars().collect::<Vec<_>>());
+        
+        // 打乱结果是基础字符集的一个排列
+        let mut sorted = chars(&a);
+        sorted.sort_unstable();
+        assert_eq!(sorted, base.chars().collect::<Vec<_>>());
+        
+        let encrypted = a.encrypt("HELLO", "KEY").unwrap();
+        assert_eq!(b.decrypt(&encrypted, "KEY").unwrap(), "HELLO");
+        
+        assert_eq!(StringCipher::shuffled("", 1).unwrap_err(), CipherError::EmptyCharset);
+        assert_eq!(StringCipher::shuffled("ABA", 1).unwrap_err(), CipherError::DuplicateChar('A'));
     }
     
     // === 泛型 VigenereCipher 测试 ===
@@ -394,6 +4799,72 @@ mod tests {
         assert_eq!(decrypted[2].to_char(), '3');
     }
     
+    #[test]
+    fn test_generic_cipher_with_modulus() {
+        let charset: Vec<CharElement> = "ABCDEFGHIJKLMNOPQRSTUVWXYZ"
+            .chars()
+            .enumerate()
+            .map(|(i, c)| CharElement::new(c, i))
+            .collect();
+        let full = NonEmptyVec::try_from_vec(charset.clone()).unwrap();
+        
+        let cipher = VigenereCipher::with_modulus(full.clone(), 10).unwrap();
+        assert_eq!(cipher.modulus(), 10);
+        assert_eq!(cipher.charset().len(), 26);
+        
+        let key = [charset[1].clone()]; // B
+        let key = NonEmptySliceRef::new(key.as_slice()).unwrap();
+        
+        // J(9) + B(1) = 10 ≡ 0 (mod 10) -> A
+        let encrypted = cipher.encrypt(&[charset[9].clone(), charset[3].clone()], key);
+        assert_eq!(encrypted[0].value(), 'A');
+        assert_eq!(encrypted[1].value(), 'E');
+        
+        // 超出模数的元素也能作为输入：Z(25) + B(1) = 26 ≡ 6 (mod 10) -> G
+        assert_eq!(cipher.encrypt(&[charset[25].clone()], key)[0].value(), 'G');
+        let big_key = [charset[25].clone()]; // Z ≡ 5 (mod 10)
+        let big_key = NonEmptySliceRef::new(big_key.as_slice()).unwrap();
+        assert_eq!(cipher.decrypt(&[charset[2].clone()], big_key)[0].value(), 'H');
+        
+        assert_eq!(
+            VigenereCipher::with_modulus(full.clone(), 27).unwrap_err(),
+            CipherError::InvalidModulus { modulus: 27, charset_len: 26 }
+        );
+        assert!(VigenereCipher::with_modulus(full, 0).is_err());
+    }
+    
+    #[test]
+    fn test_generic_cipher_encrypt_by_indices() {
+        let charset: Vec<DigitElement> = (0..10).map(|i| DigitElement::new(i).unwrap()).collect();
+        let cipher = VigenereCipher::new(NonEmptyVec::try_from_vec(charset.clone()).unwrap());
+        
+        let plaintext = vec![charset[1].clone(), charset[2].clone(), charset[3].clone(), charset[9].clone()];
+        let key_elements = vec![charset[4].clone(), charset[8].clone()];
+        let key_indices = [4, 8];
+        
+        let by_elements = cipher.encrypt(&plaintext, NonEmptySliceRef::new(key_elements.as_slice()).unwrap());
+        let by_indices = cipher.encrypt_by_indices(&plaintext, NonEmptySliceRef::new(&key_indices).unwrap());
+        assert_eq!(by_indices, by_elements);
+        
+        // 超出模数的索引会取模：14 ≡ 4
+        let wrapped = cipher.encrypt_by_indices(&plaintext, NonEmptySliceRef::new(&[14, 18]).unwrap());
+        assert_eq!(wrapped, by_elements);
+        
+        let decrypted = cipher.decrypt_by_indices(&by_indices, NonEmptySliceRef::new(&key_indices).unwrap());
+        assert_eq!(decrypted, plaintext);
+    }
+    
+    #[test]
+    fn test_map_values() {
+        let elements = vec![CharElement::new('H', 7), CharElement::new('I', 8)];
+        assert_eq!(map_values(&elements), ['H', 'I']);
+        assert_eq!(map_values::<CharElement>(&[]), Vec::<char>::new());
+        
+        let cipher = StringCipher::uppercase_alpha();
+        let text: String = map_values(&cipher.to_elements("HELLO")).into_iter().collect();
+        assert_eq!(text, "HELLO");
+    }
+
     #[test]
     fn test_cipher_element_index() {
         let elem = CharElement::new('A', 0);
@@ -1,9 +1,59 @@
 //! 维吉尼亚密码交互式命令行程序
 
 use std::io::{self, Write};
-use vigenere_demo::StringCipher;
+use std::process::ExitCode;
+use vigenere_demo::{wrap_output, StringCipher};
+
+/// 命令行参数
+struct Args {
+    /// `selftest` 子命令：运行库自检后退出
+    selftest: bool,
+    /// `--wrap N`：结果每 N 个字符换行
+    wrap: Option<usize>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args { selftest: false, wrap: None };
+    let mut iter = std::env::args().skip(1);
+    
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "selftest" => args.selftest = true,
+            "--wrap" => {
+                let width = iter.next().ok_or("--wrap 需要一个宽度参数")?;
+                let width = width.parse().map_err(|_| format!("无效的宽度: {}", width))?;
+                args.wrap = Some(width);
+            }
+            other => return Err(format!("未知参数: {}", other)),
+        }
+    }
+    
+    Ok(args)
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            println!("❌ {}", e);
+            println!("用法: vigenere [selftest] [--wrap N]");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if args.selftest {
+        return match StringCipher::self_test() {
+            Ok(()) => {
+                println!("✓ 自检通过");
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                println!("❌ {}", e);
+                ExitCode::FAILURE
+            }
+        };
+    }
 
-fn main() {
     println!("=== 维吉尼亚密码加解密程序 ===\n");
     println!("架构设计:");
     println!("  • core.rs    - 泛型 VigenereCipher<T: CipherElement>");
@@ -12,13 +62,14 @@ fn main() {
     println!("  • 元素自带索引，值实现 PartialEq\n");
 
     loop {
+        let mut presets = StringCipher::presets();
+        let custom_choice = presets.len() + 1;
+
         println!("\n选择字符集:");
-        println!("1. 大写英文字母 (A-Z)");
-        println!("2. 小写英文字母 (a-z)");
-        println!("3. 大小写英文字母");
-        println!("4. 字母+数字");
-        println!("5. 可打印ASCII字符");
-        println!("6. 自定义字符集");
+        for (i, (_, label, _)) in presets.iter().enumerate() {
+            println!("{}. {}", i + 1, label);
+        }
+        println!("{}. 自定义字符集", custom_choice);
         println!("0. 退出");
 
         print!("\n请选择: ");
@@ -26,16 +77,11 @@ fn main() {
 
         let mut choice = String::new();
         io::stdin().read_line(&mut choice).unwrap();
-        let choice = choice.trim();
-
-        let cipher = match choice {
-            "0" => break,
-            "1" => StringCipher::uppercase_alpha(),
-            "2" => StringCipher::lowercase_alpha(),
-            "3" => StringCipher::mixed_alpha(),
-            "4" => StringCipher::alphanumeric(),
-            "5" => StringCipher::printable_ascii(),
-            "6" => {
+
+        let cipher = match choice.trim().parse::<usize>() {
+            Ok(0) => break,
+            Ok(n) if (1..=presets.len()).contains(&n) => presets.swap_remove(n - 1).2,
+            Ok(n) if n == custom_choice => {
                 print!("请输入自定义字符集: ");
                 io::stdout().flush().unwrap();
                 let mut custom = String::new();
@@ -60,6 +106,7 @@ fn main() {
         println!("\n选择操作:");
         println!("1. 加密");
         println!("2. 解密");
+        println!("3. 显示维吉尼亚方阵");
         print!("请选择: ");
         io::stdout().flush().unwrap();
 
@@ -70,6 +117,10 @@ fn main() {
         let is_encrypt = match operation {
             "1" => true,
             "2" => false,
+            "3" => {
+                println!("\n{}", cipher.render_tableau());
+                continue;
+            }
             _ => {
                 println!("❌ 无效选择");
                 continue;
@@ -83,6 +134,10 @@ fn main() {
         io::stdin().read_line(&mut text).unwrap();
         let text = text.trim();
 
+        if is_encrypt && cipher.looks_random(text) {
+            println!("⚠ 输入的字符分布接近均匀，可能已经是密文");
+        }
+
         // 获取密钥
         print!("请输入密钥: ");
         io::stdout().flush().unwrap();
@@ -99,7 +154,11 @@ fn main() {
 
         match result {
             Ok(output) => {
-                println!("\n✓ {}: {}", if is_encrypt { "密文" } else { "明文" }, output);
+                let label = if is_encrypt { "密文" } else { "明文" };
+                match args.wrap {
+                    Some(width) => println!("\n✓ {}:\n{}", label, wrap_output(&output, width)),
+                    None => println!("\n✓ {}: {}", label, output),
+                }
             }
             Err(e) => {
                 println!("\n❌ 错误: {}", e);
@@ -108,4 +167,5 @@ fn main() {
     }
 
     println!("\n再见！");
+    ExitCode::SUCCESS
 }
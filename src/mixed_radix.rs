@@ -0,0 +1,128 @@
+//! 混合基数密码模块
+//! 
+//! 不同位置使用不同的子字符集：第 `i` 个字符由第 `i % n` 个子密码器处理，
+//! 每个子密码器有自己的密钥和独立推进的密钥位置
+
+use crate::{normalize, CharElement, CipherElement, CipherError, StringCipher};
+
+/// 按位置轮流使用多个 [`StringCipher`] 的组合密码器
+/// 
+/// 位置按输入（规范化后）的所有字符计数，包括原样保留的字符。
+/// 字符不在当前位置对应的子字符集中时原样保留
+/// 
+/// # 示例
+/// ```
+/// use vigenere_demo::{MixedRadixCipher, StringCipher};
+/// 
+/// let digits = StringCipher::new("0123456789").unwrap();
+/// let cipher = MixedRadixCipher::new(vec![
+///     (StringCipher::uppercase_alpha(), "KEY"),
+///     (digits, "3"),
+/// ]).unwrap();
+/// 
+/// let encrypted = cipher.encrypt("A1B2C3");
+/// assert_eq!(encrypted, "K4F5A6");
+/// assert_eq!(cipher.decrypt(&encrypted), "A1B2C3");
+/// ```
+#[derive(Debug)]
+pub struct MixedRadixCipher {
+    parts: Vec<(StringCipher, Vec<CharElement>)>,
+}
+
+impl MixedRadixCipher {
+    /// 使用 `(子密码器, 密钥)` 列表创建
+    /// 
+    /// 列表为空时返回 [`CipherError::EmptyCharset`]；每个密钥必须对其子密码器有效
+    pub fn new(parts: Vec<(StringCipher, &str)>) -> Result<Self, CipherError> {
+        if parts.is_empty() {
+            return Err(CipherError::EmptyCharset);
+        }
+        
+        let parts = parts
+            .into_iter()
+            .map(|(cipher, key)| {
+                let key_elements = cipher.parse_key(key)?;
+                Ok((cipher, key_elements))
+            })
+            .collect::<Result<_, CipherError>>()?;
+        
+        Ok(Self { parts })
+    }
+    
+    /// 加密
+    /// 
+    /// 密钥已在构造时校验，加密不会失败
+    pub fn encrypt(&self, plaintext: &str) -> String {
+        self.process(plaintext, |m, k, n| (m + k) % n)
+    }
+    
+    /// 解密
+    pub fn decrypt(&self, ciphertext: &str) -> String {
+        self.process(ciphertext, |c, k, n| (c + n - k) % n)
+    }
+    
+    /// 按位置分派给子密码器处理
+    fn process<F>(&self, text: &str, operation: F) -> String
+    where
+        F: Fn(usize, usize, usize) -> usize,
+    {
+        let text = normalize(text);
+        let mut key_indices = vec![0; self.parts.len()];
+        let mut result = String::with_capacity(text.len());
+        
+        for (pos, ch) in text.chars().enumerate() {
+            let part = pos % self.parts.len();
+            let (cipher, key) = &self.parts[part];
+            match cipher.find_element(ch) {
+                Some(elem) => {
+                    let shift = key[key_indices[part] % key.len()].index();
+                    let new_index = operation(elem.index(), shift, cipher.modulus);
+                    result.push(cipher.charset[new_index].value());
+                    key_indices[part] += 1;
+                }
+                None => result.push(ch),
+            }
+        }
+        
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mixed_radix_alternating() {
+        let digits = StringCipher::new("0123456789").unwrap();
+        let cipher = MixedRadixCipher::new(vec![
+            (StringCipher::uppercase_alpha(), "LEMON"),
+            (digits, "271"),
+        ])
+        .unwrap();
+        
+        let text = "H3L1O4W1R5";
+        let encrypted = cipher.encrypt(text);
+        let letters: String = encrypted.chars().step_by(2).collect();
+        let numbers: String = encrypted.chars().skip(1).step_by(2).collect();
+        assert_eq!(letters, StringCipher::uppercase_alpha().encrypt("HLOWR", "LEMON").unwrap());
+        assert_eq!(numbers, StringCipher::new("0123456789").unwrap().encrypt("31415", "271").unwrap());
+        assert_eq!(cipher.decrypt(&encrypted), text);
+        
+        // 不属于当前位置子字符集的字符原样保留，但仍占用位置
+        assert_eq!(cipher.encrypt("1A"), "1A");
+    }
+
+    #[test]
+    fn test_mixed_radix_errors() {
+        assert_eq!(MixedRadixCipher::new(vec![]).unwrap_err(), CipherError::EmptyCharset);
+        assert_eq!(
+            MixedRadixCipher::new(vec![(StringCipher::uppercase_alpha(), "")]).unwrap_err(),
+            CipherError::EmptyKey
+        );
+        assert_eq!(
+            MixedRadixCipher::new(vec![(StringCipher::uppercase_alpha(), "key")]).unwrap_err(),
+            CipherError::CharNotInCharset('k')
+        );
+    }
+}
@@ -0,0 +1,110 @@
+//! 换位密码模块
+//! 
+//! 关键词列换位：按行写入与关键词等宽的表格，再按关键词字母的字母序逐列读出。
+//! 最后一行可以不满，不做填充
+//! 
+//! ```text
+//! 关键词: Z E B R A S      读出顺序: A(4) B(2) E(1) R(3) S(5) Z(0)
+//!         W E A R E D
+//!         I S C O V E      WEAREDISCOVERED → EV ACD ESE RO DE WIR
+//!         R E D
+//! ```
+
+/// 按关键词字母排序得到列的读出顺序（相同字母按出现位置排序）
+fn column_order(key: &[char]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..key.len()).collect();
+    order.sort_by_key(|&i| key[i]);
+    order
+}
+
+/// 关键词列换位加密
+/// 
+/// 关键词为空时原样返回
+/// 
+/// # 示例
+/// ```
+/// use vigenere_demo::transposition::columnar_transpose;
+/// 
+/// let chars: Vec<char> = "WEAREDISCOVERED".chars().collect();
+/// let transposed: String = columnar_transpose(&chars, "ZEBRAS").into_iter().collect();
+/// assert_eq!(transposed, "EVACDESERODEWIR");
+/// ```
+pub fn columnar_transpose(chars: &[char], key: &str) -> Vec<char> {
+    let key: Vec<char> = key.chars().collect();
+    if key.is_empty() {
+        return chars.to_vec();
+    }
+    
+    column_order(&key)
+        .into_iter()
+        .flat_map(|col| chars.iter().skip(col).step_by(key.len()).copied())
+        .collect()
+}
+
+/// 关键词列换位解密，[`columnar_transpose`] 的逆运算
+/// 
+/// # 示例
+/// ```
+/// use vigenere_demo::transposition::columnar_untranspose;
+/// 
+/// let chars: Vec<char> = "EVACDESERODEWIR".chars().collect();
+/// let original: String = columnar_untranspose(&chars, "ZEBRAS").into_iter().collect();
+/// assert_eq!(original, "WEAREDISCOVERED");
+/// ```
+pub fn columnar_untranspose(chars: &[char], key: &str) -> Vec<char> {
+    let key: Vec<char> = key.chars().collect();
+    if key.is_empty() {
+        return chars.to_vec();
+    }
+    
+    let width = key.len();
+    let (full_rows, remainder) = (chars.len() / width, chars.len() % width);
+    let mut result = vec!['\0'; chars.len()];
+    let mut source = chars.iter();
+    
+    for col in column_order(&key) {
+        // 最后一行不完整时，只有前 `remainder` 列多一个字符
+        let height = full_rows + usize::from(col < remainder);
+        for row in 0..height {
+            result[row * width + col] = *source.next().expect("列长度之和等于输入长度");
+        }
+    }
+    
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    #[test]
+    fn test_columnar_transpose_irregular_last_row() {
+        // 15 个字符写入 6 列：第 0-2 列 3 行，第 3-5 列 2 行
+        let transposed = columnar_transpose(&chars("WEAREDISCOVERED"), "ZEBRAS");
+        assert_eq!(transposed, chars("EVACDESERODEWIR"));
+        assert_eq!(columnar_untranspose(&transposed, "ZEBRAS"), chars("WEAREDISCOVERED"));
+    }
+
+    #[test]
+    fn test_columnar_transpose_full_rows_and_repeated_letters() {
+        // 关键词 "BAB"：列顺序 A(1), B(0), B(2)
+        let transposed = columnar_transpose(&chars("ABCDEF"), "BAB");
+        assert_eq!(transposed, chars("BEADCF"));
+        assert_eq!(columnar_untranspose(&transposed, "BAB"), chars("ABCDEF"));
+    }
+
+    #[test]
+    fn test_columnar_transpose_edge_cases() {
+        assert_eq!(columnar_transpose(&chars("HELLO"), ""), chars("HELLO"));
+        assert_eq!(columnar_untranspose(&chars("HELLO"), ""), chars("HELLO"));
+        assert!(columnar_transpose(&[], "KEY").is_empty());
+        // 关键词比文本长时，只有部分列有字符
+        let transposed = columnar_transpose(&chars("HI"), "CAB");
+        assert_eq!(transposed, chars("IH"));
+        assert_eq!(columnar_untranspose(&transposed, "CAB"), chars("HI"));
+    }
+}
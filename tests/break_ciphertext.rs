@@ -0,0 +1,38 @@
+//! 端到端破解测试：估计密钥长度 → 频率分析恢复密钥 → 解密
+
+use vigenere_demo::StringCipher;
+
+/// 英文字母频率（%），按 A-Z 排列
+const ENGLISH_FREQS: [f64; 26] = [
+    8.167, 1.492, 2.782, 4.253, 12.702, 2.228, 2.015, 6.094, 6.966, 0.153, 0.772, 4.025, 2.406,
+    6.749, 7.507, 1.929, 0.095, 5.987, 6.327, 9.056, 2.758, 0.978, 2.360, 0.150, 1.974, 0.074,
+];
+
+/// 《双城记》第一章开篇
+const PLAINTEXT: &str = "IT WAS THE BEST OF TIMES, IT WAS THE WORST OF TIMES, IT WAS THE AGE OF WISDOM, \
+    IT WAS THE AGE OF FOOLISHNESS, IT WAS THE EPOCH OF BELIEF, IT WAS THE EPOCH OF INCREDULITY, \
+    IT WAS THE SEASON OF LIGHT, IT WAS THE SEASON OF DARKNESS, IT WAS THE SPRING OF HOPE, \
+    IT WAS THE WINTER OF DESPAIR, WE HAD EVERYTHING BEFORE US, WE HAD NOTHING BEFORE US, \
+    WE WERE ALL GOING DIRECT TO HEAVEN, WE WERE ALL GOING DIRECT THE OTHER WAY. IN SHORT, \
+    THE PERIOD WAS SO FAR LIKE THE PRESENT PERIOD, THAT SOME OF ITS NOISIEST AUTHORITIES \
+    INSISTED ON ITS BEING RECEIVED, FOR GOOD OR FOR EVIL, IN THE SUPERLATIVE DEGREE OF \
+    COMPARISON ONLY. THERE WERE A KING WITH A LARGE JAW AND A QUEEN WITH A PLAIN FACE, ON THE \
+    THRONE OF ENGLAND; THERE WERE A KING WITH A LARGE JAW AND A QUEEN WITH A FAIR FACE, ON THE \
+    THRONE OF FRANCE. IN BOTH COUNTRIES IT WAS CLEARER THAN CRYSTAL TO THE LORDS OF THE STATE \
+    PRESERVES OF LOAVES AND FISHES, THAT THINGS IN GENERAL WERE SETTLED FOR EVER.";
+
+#[test]
+fn test_break_ciphertext_recovers_key_and_plaintext() {
+    let cipher = StringCipher::uppercase_alpha();
+    let ciphertext = cipher.encrypt(PLAINTEXT, "CIPHER").unwrap();
+    
+    let (key, plaintext) = cipher.break_ciphertext(&ciphertext, &ENGLISH_FREQS, 20).unwrap();
+    assert_eq!(key, "CIPHER");
+    assert_eq!(plaintext, PLAINTEXT);
+}
+
+#[test]
+fn test_break_ciphertext_rejects_non_alphabet_charset() {
+    let cipher = StringCipher::alphanumeric();
+    assert!(cipher.break_ciphertext("ABC", &ENGLISH_FREQS, 10).is_err());
+}